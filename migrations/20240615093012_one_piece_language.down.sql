@@ -0,0 +1,5 @@
+-- Add down migration script here
+DELETE FROM one_piece WHERE language <> 'ja';
+ALTER TABLE one_piece DROP CONSTRAINT one_piece_pkey;
+ALTER TABLE one_piece ADD PRIMARY KEY (code);
+ALTER TABLE one_piece DROP COLUMN IF EXISTS language;
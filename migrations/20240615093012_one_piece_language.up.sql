@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE one_piece ADD COLUMN language TEXT NOT NULL DEFAULT 'ja';
+ALTER TABLE one_piece DROP CONSTRAINT one_piece_pkey;
+ALTER TABLE one_piece ADD PRIMARY KEY (code, language);
@@ -0,0 +1,6 @@
+-- Add down migration script here
+-- Postgres can't drop enum values, so rows using the new ones are removed and
+-- the extra labels are left in place.
+DELETE FROM one_piece WHERE rarity IN ('TR', 'DON', 'Unknown') OR type IN ('Don', 'Unknown');
+ALTER TABLE one_piece DROP COLUMN IF EXISTS rarity_raw;
+ALTER TABLE one_piece DROP COLUMN IF EXISTS type_raw;
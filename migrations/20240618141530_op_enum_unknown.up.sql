@@ -0,0 +1,8 @@
+-- Add up migration script here
+ALTER TYPE op_rarity_enum ADD VALUE IF NOT EXISTS 'TR';
+ALTER TYPE op_rarity_enum ADD VALUE IF NOT EXISTS 'DON';
+ALTER TYPE op_rarity_enum ADD VALUE IF NOT EXISTS 'Unknown';
+ALTER TYPE op_type_enum ADD VALUE IF NOT EXISTS 'Don';
+ALTER TYPE op_type_enum ADD VALUE IF NOT EXISTS 'Unknown';
+ALTER TABLE one_piece ADD COLUMN rarity_raw TEXT;
+ALTER TABLE one_piece ADD COLUMN type_raw TEXT;
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS ws_card_details;
+DROP TABLE IF EXISTS ws_card_detail_fetchable;
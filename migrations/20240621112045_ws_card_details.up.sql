@@ -0,0 +1,19 @@
+-- Add up migration script here
+CREATE TABLE ws_card_detail_fetchable(
+	code TEXT PRIMARY KEY,
+	fetched BOOLEAN NOT NULL DEFAULT false
+);
+
+CREATE TABLE ws_card_details(
+	code TEXT PRIMARY KEY,
+	side TEXT,
+	color TEXT,
+	card_type TEXT,
+	level INTEGER,
+	cost INTEGER,
+	power INTEGER,
+	soul INTEGER,
+	triggers TEXT[] NOT NULL DEFAULT '{}',
+	traits TEXT[] NOT NULL DEFAULT '{}',
+	text TEXT
+);
@@ -0,0 +1,8 @@
+-- Add up migration script here
+CREATE TABLE ws_sets(
+	expansion_id TEXT PRIMARY KEY,
+	name TEXT NOT NULL,
+	position INTEGER NOT NULL,
+	card_count INTEGER,
+	completed_at TIMESTAMPTZ
+);
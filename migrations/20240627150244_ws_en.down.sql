@@ -0,0 +1,13 @@
+-- Add down migration script here
+DELETE FROM ws_sets WHERE region <> 'jp';
+ALTER TABLE ws_sets DROP CONSTRAINT ws_sets_pkey;
+ALTER TABLE ws_sets ADD PRIMARY KEY (expansion_id);
+ALTER TABLE ws_sets DROP COLUMN IF EXISTS region;
+
+DELETE FROM ws_progress WHERE region <> 'jp';
+ALTER TABLE ws_progress DROP COLUMN IF EXISTS region;
+
+DELETE FROM ws_cards WHERE language <> 'ja';
+DROP INDEX IF EXISTS ws_cards_base_code_idx;
+ALTER TABLE ws_cards DROP COLUMN IF EXISTS base_code;
+ALTER TABLE ws_cards DROP COLUMN IF EXISTS language;
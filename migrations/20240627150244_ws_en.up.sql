@@ -0,0 +1,16 @@
+-- Add up migration script here
+ALTER TABLE ws_cards ADD COLUMN language TEXT NOT NULL DEFAULT 'ja';
+ALTER TABLE ws_cards ADD COLUMN base_code TEXT;
+UPDATE ws_cards SET base_code = code;
+ALTER TABLE ws_cards ALTER COLUMN base_code SET NOT NULL;
+CREATE INDEX ws_cards_base_code_idx ON ws_cards(base_code);
+
+-- only the newest progress row was ever read, keep it as the JP one
+DELETE FROM ws_progress WHERE id <> (SELECT MAX(id) FROM ws_progress);
+ALTER TABLE ws_progress ADD COLUMN region TEXT NOT NULL DEFAULT 'jp';
+ALTER TABLE ws_progress ADD CONSTRAINT ws_progress_region_key UNIQUE (region);
+INSERT INTO ws_progress(region) VALUES('en');
+
+ALTER TABLE ws_sets ADD COLUMN region TEXT NOT NULL DEFAULT 'jp';
+ALTER TABLE ws_sets DROP CONSTRAINT ws_sets_pkey;
+ALTER TABLE ws_sets ADD PRIMARY KEY (region, expansion_id);
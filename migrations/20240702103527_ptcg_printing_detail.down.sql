@@ -0,0 +1,11 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS pokemon_trainer_attack;
+ALTER TABLE pokemon_trainer_printing DROP COLUMN IF EXISTS evolve_marker;
+ALTER TABLE pokemon_trainer_printing DROP COLUMN IF EXISTS img_src;
+ALTER TABLE pokemon_trainer_printing DROP COLUMN IF EXISTS hp;
+ALTER TABLE pokemon_trainer_printing DROP COLUMN IF EXISTS weak_point;
+ALTER TABLE pokemon_trainer_printing DROP COLUMN IF EXISTS resist;
+ALTER TABLE pokemon_trainer_printing DROP COLUMN IF EXISTS escape;
+ALTER TABLE pokemon_trainer_printing DROP COLUMN IF EXISTS expansion_symbol;
+ALTER TABLE pokemon_trainer_printing DROP COLUMN IF EXISTS energy;
+ALTER TABLE pokemon_trainer_printing DROP COLUMN IF EXISTS artist;
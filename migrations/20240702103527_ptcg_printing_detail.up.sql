@@ -0,0 +1,20 @@
+-- Add up migration script here
+ALTER TABLE pokemon_trainer_printing ADD COLUMN evolve_marker TEXT;
+ALTER TABLE pokemon_trainer_printing ADD COLUMN img_src TEXT;
+ALTER TABLE pokemon_trainer_printing ADD COLUMN hp TEXT;
+ALTER TABLE pokemon_trainer_printing ADD COLUMN weak_point TEXT;
+ALTER TABLE pokemon_trainer_printing ADD COLUMN resist TEXT;
+ALTER TABLE pokemon_trainer_printing ADD COLUMN escape TEXT;
+ALTER TABLE pokemon_trainer_printing ADD COLUMN expansion_symbol TEXT;
+ALTER TABLE pokemon_trainer_printing ADD COLUMN energy TEXT;
+ALTER TABLE pokemon_trainer_printing ADD COLUMN artist TEXT;
+
+CREATE TABLE pokemon_trainer_attack(
+	printing_code TEXT NOT NULL REFERENCES pokemon_trainer_printing(code) ON DELETE CASCADE,
+	position INTEGER NOT NULL,
+	name TEXT NOT NULL,
+	cost TEXT[] NOT NULL DEFAULT '{}',
+	damage TEXT,
+	effect TEXT,
+	PRIMARY KEY (printing_code, position)
+);
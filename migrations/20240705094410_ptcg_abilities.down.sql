@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS tcg_collector_attack;
+DROP TABLE IF EXISTS pokemon_trainer_ability;
+ALTER TABLE pokemon_trainer_printing DROP COLUMN IF EXISTS rules;
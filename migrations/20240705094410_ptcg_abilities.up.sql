@@ -0,0 +1,20 @@
+-- Add up migration script here
+ALTER TABLE pokemon_trainer_printing ADD COLUMN rules TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE TABLE pokemon_trainer_ability(
+	printing_code TEXT NOT NULL REFERENCES pokemon_trainer_printing(code) ON DELETE CASCADE,
+	position INTEGER NOT NULL,
+	name TEXT NOT NULL,
+	effect TEXT,
+	PRIMARY KEY (printing_code, position)
+);
+
+CREATE TABLE tcg_collector_attack(
+	card_url TEXT NOT NULL,
+	position INTEGER NOT NULL,
+	name TEXT NOT NULL,
+	cost TEXT[] NOT NULL DEFAULT '{}',
+	damage TEXT,
+	effect TEXT,
+	PRIMARY KEY (card_url, position)
+);
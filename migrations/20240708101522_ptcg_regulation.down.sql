@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE pokemon_trainer_printing DROP COLUMN IF EXISTS regulation_mark;
+ALTER TABLE pokemon_trainer_printing DROP COLUMN IF EXISTS energy_type;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE pokemon_trainer_printing ADD COLUMN regulation_mark TEXT;
+ALTER TABLE pokemon_trainer_printing ADD COLUMN energy_type TEXT;
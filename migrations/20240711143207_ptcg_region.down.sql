@@ -0,0 +1,29 @@
+-- Add down migration script here
+ALTER TABLE pokemon_trainer_ability DROP CONSTRAINT pokemon_trainer_ability_region_printing_code_fkey;
+ALTER TABLE pokemon_trainer_ability DROP CONSTRAINT pokemon_trainer_ability_pkey;
+ALTER TABLE pokemon_trainer_ability DROP COLUMN region;
+ALTER TABLE pokemon_trainer_ability ADD PRIMARY KEY (printing_code, position);
+
+ALTER TABLE pokemon_trainer_attack DROP CONSTRAINT pokemon_trainer_attack_region_printing_code_fkey;
+ALTER TABLE pokemon_trainer_attack DROP CONSTRAINT pokemon_trainer_attack_pkey;
+ALTER TABLE pokemon_trainer_attack DROP COLUMN region;
+ALTER TABLE pokemon_trainer_attack ADD PRIMARY KEY (printing_code, position);
+
+ALTER TABLE pokemon_trainer_printing DROP CONSTRAINT expansion_code_number_key;
+ALTER TABLE pokemon_trainer_printing DROP CONSTRAINT pokemon_trainer_printing_region_code_key;
+ALTER TABLE pokemon_trainer_printing DROP COLUMN region;
+ALTER TABLE pokemon_trainer_printing ADD CONSTRAINT pokemon_trainer_printing_code_key UNIQUE(code);
+ALTER TABLE pokemon_trainer_printing ADD CONSTRAINT expansion_code_number_key UNIQUE(name, number, expansion_code);
+
+ALTER TABLE pokemon_trainer_attack ADD FOREIGN KEY (printing_code)
+	REFERENCES pokemon_trainer_printing(code) ON DELETE CASCADE;
+ALTER TABLE pokemon_trainer_ability ADD FOREIGN KEY (printing_code)
+	REFERENCES pokemon_trainer_printing(code) ON DELETE CASCADE;
+
+ALTER TABLE pokemon_trainer_fetchable_card DROP CONSTRAINT pokemon_trainer_fetchable_card_region_code_key;
+ALTER TABLE pokemon_trainer_fetchable_card DROP COLUMN region;
+ALTER TABLE pokemon_trainer_fetchable_card ADD CONSTRAINT pokemon_trainer_fetchable_card_code_key UNIQUE(code);
+
+ALTER TABLE pokemon_trainer_expansion DROP CONSTRAINT pokemon_trainer_expansion_region_code_key;
+ALTER TABLE pokemon_trainer_expansion DROP COLUMN region;
+ALTER TABLE pokemon_trainer_expansion ADD CONSTRAINT pokemon_trainer_expansion_code_key UNIQUE(code);
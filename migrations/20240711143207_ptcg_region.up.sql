@@ -0,0 +1,29 @@
+-- Add up migration script here
+ALTER TABLE pokemon_trainer_expansion ADD COLUMN region TEXT NOT NULL DEFAULT 'tw';
+ALTER TABLE pokemon_trainer_expansion DROP CONSTRAINT pokemon_trainer_expansion_code_key;
+ALTER TABLE pokemon_trainer_expansion ADD CONSTRAINT pokemon_trainer_expansion_region_code_key UNIQUE(region, code);
+
+ALTER TABLE pokemon_trainer_fetchable_card ADD COLUMN region TEXT NOT NULL DEFAULT 'tw';
+ALTER TABLE pokemon_trainer_fetchable_card DROP CONSTRAINT pokemon_trainer_fetchable_card_code_key;
+ALTER TABLE pokemon_trainer_fetchable_card ADD CONSTRAINT pokemon_trainer_fetchable_card_region_code_key UNIQUE(region, code);
+
+ALTER TABLE pokemon_trainer_attack DROP CONSTRAINT pokemon_trainer_attack_printing_code_fkey;
+ALTER TABLE pokemon_trainer_ability DROP CONSTRAINT pokemon_trainer_ability_printing_code_fkey;
+
+ALTER TABLE pokemon_trainer_printing ADD COLUMN region TEXT NOT NULL DEFAULT 'tw';
+ALTER TABLE pokemon_trainer_printing DROP CONSTRAINT pokemon_trainer_printing_code_key;
+ALTER TABLE pokemon_trainer_printing ADD CONSTRAINT pokemon_trainer_printing_region_code_key UNIQUE(region, code);
+ALTER TABLE pokemon_trainer_printing DROP CONSTRAINT expansion_code_number_key;
+ALTER TABLE pokemon_trainer_printing ADD CONSTRAINT expansion_code_number_key UNIQUE(region, name, number, expansion_code);
+
+ALTER TABLE pokemon_trainer_attack ADD COLUMN region TEXT NOT NULL DEFAULT 'tw';
+ALTER TABLE pokemon_trainer_attack DROP CONSTRAINT pokemon_trainer_attack_pkey;
+ALTER TABLE pokemon_trainer_attack ADD PRIMARY KEY (region, printing_code, position);
+ALTER TABLE pokemon_trainer_attack ADD FOREIGN KEY (region, printing_code)
+	REFERENCES pokemon_trainer_printing(region, code) ON DELETE CASCADE;
+
+ALTER TABLE pokemon_trainer_ability ADD COLUMN region TEXT NOT NULL DEFAULT 'tw';
+ALTER TABLE pokemon_trainer_ability DROP CONSTRAINT pokemon_trainer_ability_pkey;
+ALTER TABLE pokemon_trainer_ability ADD PRIMARY KEY (region, printing_code, position);
+ALTER TABLE pokemon_trainer_ability ADD FOREIGN KEY (region, printing_code)
+	REFERENCES pokemon_trainer_printing(region, code) ON DELETE CASCADE;
@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE pokemon_trainer_fetchable_card DROP COLUMN IF EXISTS removed_at;
+ALTER TABLE pokemon_trainer_fetchable_card DROP COLUMN IF EXISTS last_seen_at;
+ALTER TABLE pokemon_trainer_fetchable_card DROP COLUMN IF EXISTS updated_at;
+ALTER TABLE pokemon_trainer_expansion DROP COLUMN IF EXISTS last_seen_at;
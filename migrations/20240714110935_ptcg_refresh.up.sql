@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE pokemon_trainer_expansion ADD COLUMN last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+ALTER TABLE pokemon_trainer_fetchable_card ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE pokemon_trainer_fetchable_card ADD COLUMN last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE pokemon_trainer_fetchable_card ADD COLUMN removed_at TIMESTAMPTZ;
@@ -0,0 +1,11 @@
+-- Add up migration script here
+CREATE TABLE card_revisions(
+	id BIGSERIAL PRIMARY KEY,
+	source TEXT NOT NULL,
+	card_key TEXT NOT NULL,
+	field TEXT NOT NULL,
+	old_value TEXT,
+	new_value TEXT,
+	changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX card_revisions_changed_at_idx ON card_revisions(changed_at);
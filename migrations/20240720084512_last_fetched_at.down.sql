@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE pokemon_trainer_printing DROP COLUMN IF EXISTS last_fetched_at;
+ALTER TABLE yugioh_printing_detail DROP COLUMN IF EXISTS last_fetched_at;
+ALTER TABLE one_piece DROP COLUMN IF EXISTS last_fetched_at;
+ALTER TABLE ws_cards DROP COLUMN IF EXISTS last_fetched_at;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+ALTER TABLE pokemon_trainer_printing ADD COLUMN last_fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE yugioh_printing_detail ADD COLUMN last_fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE one_piece ADD COLUMN last_fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE ws_cards ADD COLUMN last_fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
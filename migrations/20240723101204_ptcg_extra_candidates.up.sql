@@ -0,0 +1,15 @@
+-- Add up migration script here
+CREATE TABLE ptcg_extra_candidates(
+	id BIGSERIAL PRIMARY KEY,
+	code TEXT NOT NULL UNIQUE,
+	source_code TEXT NOT NULL,
+	source_url TEXT NOT NULL,
+	name_en TEXT NOT NULL,
+	number TEXT NOT NULL,
+	expansion_code TEXT NOT NULL,
+	rarity ptcg_rarity_enum,
+	confidence REAL NOT NULL,
+	status TEXT NOT NULL DEFAULT 'pending',
+	created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	reviewed_at TIMESTAMPTZ
+);
@@ -0,0 +1,14 @@
+-- Add up migration script here
+CREATE TABLE ptcg_expansion_map(
+	region TEXT NOT NULL DEFAULT 'tw',
+	jp_code TEXT NOT NULL REFERENCES ptcg_jp_expansions(code) ON DELETE CASCADE,
+	tw_code TEXT NOT NULL,
+	en_code TEXT,
+	score REAL,
+	source TEXT NOT NULL DEFAULT 'auto',
+	updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	PRIMARY KEY (region, jp_code),
+	FOREIGN KEY (region, tw_code)
+		REFERENCES pokemon_trainer_expansion(region, code) ON DELETE CASCADE
+);
+CREATE INDEX ptcg_expansion_map_tw_code_idx ON ptcg_expansion_map(region, tw_code);
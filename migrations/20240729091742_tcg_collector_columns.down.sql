@@ -0,0 +1,7 @@
+-- Add down migration script here
+ALTER TABLE tcg_collector_attack DROP CONSTRAINT IF EXISTS tcg_collector_attack_card_url_fkey;
+ALTER TABLE tcg_collector DROP CONSTRAINT IF EXISTS tcg_collector_url_key;
+ALTER TABLE tcg_collector DROP COLUMN IF EXISTS hp;
+ALTER TABLE tcg_collector DROP COLUMN IF EXISTS description;
+UPDATE tcg_collector SET html = '' WHERE html IS NULL;
+ALTER TABLE tcg_collector ALTER COLUMN html SET NOT NULL;
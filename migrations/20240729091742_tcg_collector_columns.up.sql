@@ -0,0 +1,11 @@
+-- Add up migration script here
+DELETE FROM tcg_collector a USING tcg_collector b
+	WHERE a.url = b.url AND a.ctid < b.ctid;
+-- kept until `ptcg-jp tc-backfill` has parsed the stored pages into the new columns
+ALTER TABLE tcg_collector ALTER COLUMN html DROP NOT NULL;
+ALTER TABLE tcg_collector ADD COLUMN description TEXT;
+ALTER TABLE tcg_collector ADD COLUMN hp TEXT;
+ALTER TABLE tcg_collector ADD CONSTRAINT tcg_collector_url_key UNIQUE(url);
+DELETE FROM tcg_collector_attack WHERE card_url NOT IN (SELECT url FROM tcg_collector);
+ALTER TABLE tcg_collector_attack ADD FOREIGN KEY (card_url)
+	REFERENCES tcg_collector(url) ON DELETE CASCADE;
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS ptcg_en_cards;
+DROP TABLE IF EXISTS ptcg_en_expansions;
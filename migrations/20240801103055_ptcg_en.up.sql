@@ -0,0 +1,20 @@
+-- Add up migration script here
+CREATE TABLE ptcg_en_expansions(
+	code TEXT PRIMARY KEY,
+	name_en TEXT NOT NULL,
+	exp_link TEXT,
+	symbol_src TEXT,
+	logo_src TEXT,
+	release_date DATE NOT NULL
+);
+
+CREATE TABLE ptcg_en_cards(
+	url TEXT PRIMARY KEY,
+	name TEXT NOT NULL,
+	number TEXT NOT NULL,
+	exp_code TEXT NOT NULL REFERENCES ptcg_en_expansions(code) ON DELETE CASCADE,
+	rarity ptcg_rarity_enum,
+	image_src TEXT,
+	last_fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX ptcg_en_cards_exp_code_idx ON ptcg_en_cards(exp_code);
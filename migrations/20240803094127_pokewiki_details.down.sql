@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE pokewiki DROP COLUMN IF EXISTS text_chinese;
+ALTER TABLE pokewiki DROP COLUMN IF EXISTS name_chinese;
+ALTER TABLE pokewiki DROP COLUMN IF EXISTS image_src;
+ALTER TABLE pokewiki DROP COLUMN IF EXISTS link;
+ALTER TABLE pokewiki DROP COLUMN IF EXISTS card_type;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE pokewiki ADD COLUMN card_type TEXT;
+ALTER TABLE pokewiki ADD COLUMN link TEXT;
+ALTER TABLE pokewiki ADD COLUMN image_src TEXT;
+ALTER TABLE pokewiki ADD COLUMN name_chinese TEXT;
+ALTER TABLE pokewiki ADD COLUMN text_chinese TEXT;
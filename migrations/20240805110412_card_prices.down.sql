@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS card_prices;
+ALTER TABLE bigweb_pokemon_printing ALTER COLUMN sale_price TYPE INTEGER;
+ALTER TABLE bigweb_pokemon_printing DROP COLUMN IF EXISTS bigweb_id;
+ALTER TABLE bigweb_pokemon_expansion DROP COLUMN IF EXISTS bigweb_id;
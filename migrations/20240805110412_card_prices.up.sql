@@ -0,0 +1,19 @@
+-- Add up migration script here
+ALTER TABLE bigweb_pokemon_expansion ADD COLUMN bigweb_id BIGINT UNIQUE;
+ALTER TABLE bigweb_pokemon_printing ADD COLUMN bigweb_id BIGINT UNIQUE;
+ALTER TABLE bigweb_pokemon_printing ALTER COLUMN sale_price TYPE BIGINT;
+
+CREATE TABLE card_prices(
+	id BIGSERIAL PRIMARY KEY,
+	game TEXT NOT NULL,
+	set_code TEXT NOT NULL,
+	number TEXT,
+	name TEXT NOT NULL,
+	source TEXT NOT NULL,
+	card_key TEXT NOT NULL,
+	price BIGINT NOT NULL,
+	currency TEXT NOT NULL,
+	observed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX card_prices_card_idx ON card_prices(source, card_key, observed_at);
+CREATE INDEX card_prices_set_idx ON card_prices(game, LOWER(set_code), number);
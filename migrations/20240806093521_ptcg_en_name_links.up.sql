@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE ptcg_en_name_links(
+	id BIGSERIAL PRIMARY KEY,
+	en_url TEXT NOT NULL REFERENCES ptcg_en_cards(url) ON DELETE CASCADE,
+	region TEXT NOT NULL DEFAULT 'tw',
+	printing_code TEXT NOT NULL,
+	score REAL NOT NULL,
+	signals TEXT[] NOT NULL DEFAULT '{}',
+	status TEXT NOT NULL DEFAULT 'pending',
+	created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	reviewed_at TIMESTAMPTZ,
+	UNIQUE (en_url, region, printing_code),
+	FOREIGN KEY (region, printing_code)
+		REFERENCES pokemon_trainer_printing(region, code) ON DELETE CASCADE
+);
+CREATE INDEX ptcg_en_name_links_status_idx ON ptcg_en_name_links(status);
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE card_prices DROP COLUMN IF EXISTS condition;
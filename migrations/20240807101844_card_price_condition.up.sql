@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE card_prices ADD COLUMN condition TEXT NOT NULL DEFAULT 'normal';
+UPDATE card_prices cp SET condition = 'damaged'
+FROM bigweb_pokemon_printing bp
+WHERE cp.source = 'bigweb' AND cp.card_key = bp.bigweb_id::TEXT
+	AND (bp.remark LIKE '%キズ%' OR bp.remark LIKE '%傷%' OR bp.remark LIKE '%難あり%');
@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- the energy type is derived from `energy`, the symbol the detail page shows
+ALTER TABLE pokemon_trainer_printing DROP COLUMN energy_type;
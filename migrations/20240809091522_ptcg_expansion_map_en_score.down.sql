@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE ptcg_expansion_map DROP COLUMN IF EXISTS en_score;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- NULL for EN codes set by hand, which suggestions leave alone
+ALTER TABLE ptcg_expansion_map ADD COLUMN en_score REAL;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- filled by walking the card search per energy type, like regulation_mark
+ALTER TABLE pokemon_trainer_printing ADD COLUMN IF NOT EXISTS energy_type TEXT;
@@ -1,25 +1,54 @@
 mod one_piece;
+mod prices;
 pub mod ptcg;
+mod ptcg_en;
 mod ptcg_jp;
 mod ws;
 mod yugioh;
 
 use google_cloud_storage::http::objects::upload::{Media, UploadObjectRequest, UploadType};
 
-use self::{one_piece::OnePiece, ptcg::Ptcg, ptcg_jp::PtcgJp, ws::Ws, yugioh::Yugioh};
+use self::{
+    one_piece::{OnePiece, OnePieceCards},
+    prices::Prices,
+    ptcg::Ptcg,
+    ptcg_en::PtcgEn,
+    ptcg_jp::PtcgJp,
+    ws::Ws,
+    yugioh::Yugioh,
+};
 use crate::{
+    domain::{ListingCandidate, ListingMatchRow},
+    matching,
     repository::Repository,
     scraper::{
-        one_piece::OnePieceScraper, pokemon_wiki::PokemonWikiScraper, ptcg::PtcgScraper,
-        tcg_collector::TcgCollectorScraper, ws::WsScraper, yugioh::YugiohScraper,
+        bigweb::BigwebScraper,
+        one_piece::{OnePieceRegion, OnePieceScraper},
+        pokemon_wiki::PokemonWikiScraper,
+        ptcg::{PtcgRegion, PtcgScraper},
+        tcg_collector::TcgCollectorScraper,
+        ws::{WsRegion, WsScraper},
+        yugioh::YugiohScraper,
     },
+    title::ListingTitle,
+};
+use serde::Deserialize;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    io::{Read, Write},
+    path::Path,
 };
-use std::{borrow::Cow, io::Write, path::Path};
+
+#[derive(Deserialize)]
+struct ListingRow {
+    title: String,
+}
 
 async fn download<T: AsRef<Path>>(url: url::Url, save_path: T) -> Result<(), crate::error::Error> {
     let result = reqwest::get(url).await?;
-    let paths = result.url().path_segments().unwrap();
-    let file_name = paths.last().unwrap();
+    let mut paths = result.url().path_segments().unwrap();
+    let file_name = paths.next_back().unwrap();
     let save_path = save_path.as_ref().join(file_name);
     let mut file = std::fs::OpenOptions::new()
         .create(true)
@@ -71,21 +100,26 @@ impl Application {
         let repository = Repository::from_dsn(url).unwrap();
         Self { repository }
     }
-    pub fn ptcg(&self) -> Ptcg {
-        let scraper = PtcgScraper::new();
+    pub fn ptcg(&self, region: PtcgRegion) -> Ptcg {
+        let scraper = PtcgScraper { region };
         Ptcg {
             repository: self.repository.clone(),
             scraper,
             wiki_scraper: PokemonWikiScraper::new(),
         }
     }
-    pub fn one_piece(&self) -> OnePiece {
-        let scraper = OnePieceScraper {};
+    pub fn one_piece(&self, region: OnePieceRegion) -> OnePiece {
+        let scraper = OnePieceScraper { region };
         OnePiece {
             scraper,
             repository: self.repository.clone(),
         }
     }
+    pub fn one_piece_cards(&self) -> OnePieceCards {
+        OnePieceCards {
+            repository: self.repository.clone(),
+        }
+    }
     pub fn yugioh(&self) -> Yugioh {
         let scraper = YugiohScraper::new();
         Yugioh {
@@ -93,18 +127,90 @@ impl Application {
             repository: self.repository.clone(),
         }
     }
-    pub fn ws(&self) -> Ws {
-        let scraper = WsScraper {};
+    pub fn ws(&self, region: WsRegion) -> Ws {
+        let scraper = WsScraper { region };
         Ws {
             scraper,
             repository: self.repository.clone(),
         }
     }
+    /// Writes every card field changed upstream since `since` as CSV.
+    pub async fn export_revisions<W: Write>(
+        &self,
+        since: chrono::NaiveDate,
+        w: W,
+    ) -> Result<(), crate::error::Error> {
+        let mut wtr = csv::Writer::from_writer(w);
+        for revision in self.repository.get_card_revisions(since).await? {
+            wtr.serialize(revision)?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+    /// Reads listing titles as CSV with a `title` column and writes the
+    /// printing each most likely lists.
+    pub async fn match_listings<R: Read, W: Write>(
+        &self,
+        r: R,
+        w: W,
+    ) -> Result<(), crate::error::Error> {
+        let mut rdr = csv::Reader::from_reader(r);
+        let mut wtr = csv::Writer::from_writer(w);
+        let mut candidates: HashMap<String, Vec<ListingCandidate>> = HashMap::new();
+        for result in rdr.deserialize() {
+            let ListingRow { title: raw } = result?;
+            let title = match raw.parse::<ListingTitle>() {
+                Ok(title) => title,
+                Err(err) => {
+                    wtr.serialize(ListingMatchRow {
+                        title: raw,
+                        game: None,
+                        card_id: None,
+                        card_name: None,
+                        confidence: 0.0,
+                        reasons: err.to_string(),
+                    })?;
+                    continue;
+                }
+            };
+            if !candidates.contains_key(&title.set_ref) {
+                let found = self
+                    .repository
+                    .get_listing_candidates(&title.set_ref)
+                    .await?;
+                candidates.insert(title.set_ref.clone(), found);
+            }
+            let found = matching::match_listing(&title, &candidates[&title.set_ref]);
+            wtr.serialize(ListingMatchRow {
+                title: raw,
+                game: found.candidate.map(|c| c.game.clone()),
+                card_id: found.candidate.map(|c| c.id.clone()),
+                card_name: found.candidate.map(|c| c.name.clone()),
+                confidence: found.confidence,
+                reasons: found.reasons.join("; "),
+            })?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
     pub fn ptcg_jp(&self) -> PtcgJp {
-        let scraper = TcgCollectorScraper {};
+        let scraper = TcgCollectorScraper { cache_dir: None };
         PtcgJp {
             scraper,
             repository: self.repository.clone(),
         }
     }
+    pub fn prices(&self) -> Prices {
+        Prices {
+            scraper: BigwebScraper::new(),
+            repository: self.repository.clone(),
+        }
+    }
+    pub fn ptcg_en(&self) -> PtcgEn {
+        let scraper = TcgCollectorScraper { cache_dir: None };
+        PtcgEn {
+            scraper,
+            repository: self.repository.clone(),
+        }
+    }
 }
@@ -13,24 +13,44 @@ pub struct OnePiece {
     pub repository: Repository,
 }
 
-impl OnePiece {
+/// The stored cards of every language, which need no scraper.
+pub struct OnePieceCards {
+    pub repository: Repository,
+}
+
+impl OnePieceCards {
     pub async fn download_images(&self) -> Result<(), Error> {
         self.repository
             .list_one_piece()
             .map_err(Error::from)
             .try_for_each(|card| async move {
                 let u = url::Url::parse(&card.img_src.clone())?;
-                download(u, "./images/").await?;
+                let save_path = format!("./images/{}/", card.language);
+                std::fs::create_dir_all(&save_path)?;
+                download(u, save_path).await?;
                 Ok(())
             })
             .await?;
         Ok(())
     }
+    pub async fn export_one_piece_csv<W: std::io::Write>(&self, w: W) -> Result<(), Error> {
+        let mut wtr = csv::Writer::from_writer(w);
+        let mut cards = self.repository.list_one_piece();
+        while let Some(card) = cards.try_next().await? {
+            let c: ExportCsv = card.into();
+            wtr.serialize(c)?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+impl OnePiece {
     pub async fn scrape_one_piece(&self) -> Result<(), Error> {
         let sets = self.scraper.set().await?;
         for set in sets {
             for card in self.scraper.scrape_cards(&set).await? {
-                self.repository.upsert_one_piece(card.unwrap()).await;
+                self.repository.upsert_one_piece(card).await?;
             }
         }
         Ok(())
@@ -49,16 +69,4 @@ impl OnePiece {
         wtr.flush().unwrap();
         Ok(())
     }
-    pub async fn export_one_piece_csv<W: std::io::Write>(&self, w: W) -> Result<(), Error> {
-        let sets = self.scraper.set().await?;
-        let mut wtr = csv::Writer::from_writer(w);
-        for set in sets {
-            for card in self.scraper.scrape_cards(&set).await? {
-                let c: ExportCsv = card.unwrap().into();
-                wtr.serialize(c).unwrap();
-            }
-        }
-        wtr.flush().unwrap();
-        Ok(())
-    }
 }
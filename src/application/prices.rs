@@ -0,0 +1,61 @@
+use std::io::Write;
+
+use chrono::{Days, Local};
+use tracing::info;
+
+use crate::{
+    domain::ReportFormat, error::Error, price_report, repository::Repository,
+    scraper::bigweb::BigwebScraper,
+};
+
+pub struct Prices {
+    pub scraper: BigwebScraper,
+    pub repository: Repository,
+}
+
+impl Prices {
+    /// Records today's bigweb price of every card in a set with a set code;
+    /// supplies and bundles have none and are only stored.
+    pub async fn update_bigweb(&self) -> Result<(), Error> {
+        let cardsets = self.scraper.fetch_cardsets().await?;
+        for cardset in cardsets {
+            self.repository.save_bigweb_cardset(&cardset).await?;
+            if cardset.code.as_deref().unwrap_or_default().is_empty() {
+                continue;
+            }
+            let products = self.scraper.fetch_products(cardset.id).await?;
+            info!("{}: {} products", cardset.name, products.len());
+            self.repository
+                .save_bigweb_products(&cardset, products)
+                .await?;
+        }
+        Ok(())
+    }
+    /// Writes the `top` movers of each game and set, and any anomaly, from
+    /// prices of the last `days` days.
+    pub async fn report<W: Write>(
+        &self,
+        days: u64,
+        top: usize,
+        format: ReportFormat,
+        mut w: W,
+    ) -> Result<(), Error> {
+        let since = Local::now().date_naive() - Days::new(days);
+        let points = self.repository.get_card_prices(since).await?;
+        let rows = price_report::report(&points, top);
+        match format {
+            ReportFormat::Csv => {
+                let mut wtr = csv::Writer::from_writer(w);
+                for row in rows {
+                    wtr.serialize(row)?;
+                }
+                wtr.flush()?;
+            }
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(&mut w, &rows)?;
+                writeln!(w)?;
+            }
+        }
+        Ok(())
+    }
+}
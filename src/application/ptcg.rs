@@ -1,13 +1,23 @@
 use crate::{
-    domain::{PokemonCard, PtcgRarity},
+    domain::PtcgRarity,
     error::Error,
+    export::export_csv::ExportCsv,
+    matching,
     repository::Repository,
-    scraper::{pokemon_wiki::PokemonWikiScraper, ptcg::PtcgScraper},
-    strategy::{ManualStrategy, PtcgStrategy, Source, TcgCollectorStrategy, WikiStrategy},
+    scraper::{
+        pokemon_wiki::PokemonWikiScraper,
+        ptcg::{PtcgScraper, ENERGY_TYPES},
+        rarity::RaritySource,
+    },
+    strategy::{
+        ManualStrategy, PtcgStrategy, Source, TcgCollectorStrategy, WikiData, WikiStrategy,
+    },
     PtcgExpansionDbRow,
 };
 use futures::{StreamExt, TryStreamExt};
+use std::collections::{HashMap, HashSet};
 use strum::IntoEnumIterator;
+use tracing::{info, warn};
 use url::Url;
 
 use super::download;
@@ -20,7 +30,44 @@ pub struct Ptcg {
 }
 
 impl Ptcg {
-    pub async fn from_expansion(
+    /// Proposes a wiki strategy row for each expansion whose code matches a
+    /// wiki set page, skipping the expansions in `existing`.
+    pub async fn discover_wiki_strategies<W: std::io::Write>(
+        &self,
+        existing: &HashSet<String>,
+        w: W,
+    ) -> Result<(), Error> {
+        let pages = self.wiki_scraper.fetch_set_pages().await?;
+        let pages: HashMap<_, _> = pages
+            .into_iter()
+            .filter_map(|page| Some((matching::normalize_code(page.code.as_ref()?), page.url)))
+            .collect();
+        let exps: Vec<_> = self
+            .repository
+            .find_ptcg_expansion(self.scraper.region)
+            .try_collect()
+            .await?;
+        let mut wtr = csv::Writer::from_writer(w);
+        for exp in exps {
+            if existing.contains(&exp.code) {
+                continue;
+            }
+            let Some(url) = pages.get(&matching::normalize_code(&exp.code)) else {
+                continue;
+            };
+            let sources = vec![Source::Wiki(WikiStrategy::Data(WikiData::new(Url::parse(
+                url,
+            )?)))];
+            wtr.serialize(PtcgExpansionDbRow {
+                exp: exp.code,
+                name: exp.name,
+                strategy: serde_json::to_string(&sources)?,
+            })?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+    pub async fn run_strategy(
         &self,
         sources: Vec<Source>,
         record: PtcgExpansionDbRow,
@@ -55,7 +102,7 @@ impl Ptcg {
                 Source::Wiki(WikiStrategy::Data(data)) => {
                     let cards = self
                         .wiki_scraper
-                        .fetch_card_data_by_exp_url(&data.url().to_string(), &record.exp)
+                        .fetch_card_data_by_exp_url(data.url().as_ref(), &record.exp, data.range())
                         .await?;
                     self.repository.upsert_pokewiki(cards).await?;
                 }
@@ -68,42 +115,85 @@ impl Ptcg {
         Ok(())
     }
     pub async fn download_all_image(&self) -> Result<(), Error> {
-        let codes = self.repository.get_ptcg_codes();
+        let region = self.scraper.region;
+        let save_path = format!("./images/ptcg/{region}/");
+        std::fs::create_dir_all(&save_path)?;
+        let codes = self.repository.get_ptcg_printing_codes(region);
         codes
             .map_err(Error::from)
-            .try_for_each(|code| async move {
-                let code: i32 = code.parse().unwrap();
-                let image_url = format!(
-                    "https://asia.pokemon-card.com/tw/card-img/tw{:08}.png",
-                    code
-                );
-                let image_url = Url::parse(&image_url)?;
-                download(image_url, "./images/").await?;
-                Ok(())
+            .try_for_each(|code| {
+                let save_path = save_path.clone();
+                async move {
+                    let code: i32 = code.parse()?;
+                    let image_url = Url::parse(&region.image_url(code))?;
+                    download(image_url, save_path).await?;
+                    Ok(())
+                }
             })
             .await?;
         Ok(())
     }
     pub async fn prepare_ptcg_expansions(&self) -> Result<(), Error> {
-        let count = self.repository.find_ptcg_expansion().count().await;
+        let region = self.scraper.region;
+        let count = self.repository.find_ptcg_expansion(region).count().await;
         if count == 0 {
             let expansions = self.scraper.fetch_expansion().await?;
             for exp in expansions {
-                self.repository.upsert_ptcg_expansion(&exp).await?;
+                self.repository.upsert_ptcg_expansion(region, &exp).await?;
+            }
+        }
+        Ok(())
+    }
+    /// Diff the live expansion list and card codes against the DB, enqueueing
+    /// only new cards and flagging cards that disappeared from the site.
+    ///
+    /// An expansion listing no cards is left alone, since that is far more
+    /// likely a failed fetch than an emptied expansion. Expansions missing from
+    /// the site are only warned about; nothing records their removal.
+    pub async fn refresh(&self) -> Result<(), Error> {
+        let region = self.scraper.region;
+        let expansions = self.scraper.fetch_expansion().await?;
+        if !expansions.is_empty() {
+            let live: HashSet<&str> = expansions.iter().map(|e| e.code.as_str()).collect();
+            let known: Vec<String> = self.repository.get_ptcg_codes(region).try_collect().await?;
+            for code in known.iter().filter(|code| !live.contains(code.as_str())) {
+                warn!("ptcg expansion {code} is no longer listed on the {region} site");
+            }
+        }
+        for exp in expansions {
+            if self.repository.upsert_ptcg_expansion(region, &exp).await? {
+                info!("new ptcg expansion {} {}", exp.code, exp.name);
+            }
+            let live_codes = self.scraper.get_fetchables_by_exp(&exp.code).await?;
+            if live_codes.is_empty() {
+                warn!("ptcg expansion {} lists no cards, skipping", exp.code);
+                continue;
+            }
+            let (new, removed) = self
+                .repository
+                .refresh_fetchable(region, &live_codes, &exp.code)
+                .await?;
+            if new > 0 || removed > 0 {
+                info!("ptcg expansion {}: {new} new, {removed} removed", exp.code);
             }
         }
         Ok(())
     }
     pub async fn update_ptcg_fetchable(&self) -> Result<(), Error> {
-        let codes = self.repository.get_ptcg_codes();
+        let region = self.scraper.region;
+        let codes = self.repository.get_ptcg_codes(region);
         codes
             .map_err(Error::from)
             .try_for_each(|code| async move {
-                let count = self.repository.get_fetchable_by_code(&code).count().await;
+                let count = self
+                    .repository
+                    .get_fetchable_by_code(region, &code)
+                    .count()
+                    .await;
                 if count == 0 {
                     let fetchable_codes = self.scraper.get_fetchables_by_exp(&code).await?;
                     self.repository
-                        .upsert_fetchable(fetchable_codes, &code)
+                        .upsert_fetchable(region, fetchable_codes, &code)
                         .await?;
                 }
                 Ok(())
@@ -112,19 +202,15 @@ impl Ptcg {
         Ok(())
     }
     pub async fn update_ptcg_printing(&self) -> Result<(), Error> {
+        let region = self.scraper.region;
         self.repository
-            .get_fetchable()
+            .get_fetchable(region)
             .map_err(Error::from)
             .try_for_each(|(code, set_code)| async move {
-                let mut card = self
-                    .scraper
-                    .fetch_printing_detail(&format!(
-                        "https://asia.pokemon-card.com/tw/card-search/detail/{code}/"
-                    ))
-                    .await?;
+                let mut card = self.scraper.fetch_printing_detail(&code).await?;
                 card.set_code = Some(set_code);
-                self.repository.upsert_the_ptcg_card(&card).await;
-                self.repository.fetched(&code).await;
+                self.repository.upsert_the_ptcg_card(region, &card).await?;
+                self.repository.fetched(region, &code).await;
                 Ok(())
             })
             .await?;
@@ -132,13 +218,56 @@ impl Ptcg {
     }
     pub async fn update_rarity(&self) -> Result<(), Error> {
         for rarity in PtcgRarity::iter() {
-            let ids = self.scraper.rarity_ids(&rarity).await?;
-            self.repository.update_the_ptcg_rarity(ids, &rarity).await?;
+            let Some(label) = RaritySource::TwSite.label(&rarity) else {
+                warn!("{rarity} has no {} label, skipped", RaritySource::TwSite);
+                continue;
+            };
+            let ids = self.scraper.rarity_ids(label).await?;
+            self.repository
+                .update_the_ptcg_rarity(self.scraper.region, ids, &rarity)
+                .await?;
         }
         Ok(())
     }
-    pub async fn export_pokemon_trainer(&self) -> Result<Vec<PokemonCard>, Error> {
-        let all_cards = self.repository.get_all_pokemon_trainer_printing();
-        Ok(all_cards.collect().await)
+    pub async fn update_regulation(&self) -> Result<(), Error> {
+        let marks = self.scraper.search_form_values("regulation").await?;
+        if marks.is_empty() {
+            warn!("no regulation marks in the card search form, skipped");
+        }
+        for mark in &marks {
+            let ids = self.scraper.regulation_ids(mark).await?;
+            self.repository
+                .update_the_ptcg_regulation(self.scraper.region, ids, mark)
+                .await?;
+        }
+        Ok(())
+    }
+    /// Walks the card search once per energy type the form offers, the way
+    /// rarity and regulation marks are collected.
+    pub async fn update_energy_type(&self) -> Result<(), Error> {
+        let energy_ids = self.scraper.search_form_values("pokemonEnergy").await?;
+        for energy_id in &energy_ids {
+            let Some((_, energy_type)) = ENERGY_TYPES.iter().find(|(id, _)| id == energy_id) else {
+                warn!("unknown pokemonEnergy id {energy_id} in the card search form, skipped");
+                continue;
+            };
+            let ids = self.scraper.energy_type_ids(energy_id).await?;
+            self.repository
+                .update_the_ptcg_energy_type(self.scraper.region, ids, energy_type)
+                .await?;
+        }
+        Ok(())
+    }
+    pub async fn export_pokemon_trainer<W: std::io::Write>(&self, w: W) -> Result<(), Error> {
+        let mut wtr = csv::Writer::from_writer(w);
+        let mut cards = self
+            .repository
+            .get_all_pokemon_trainer_printing(self.scraper.region);
+        while let Some(card) = cards.next().await {
+            let c: ExportCsv = card.into();
+            wtr.serialize(c)?;
+        }
+        wtr.flush()?;
+        Ok(())
     }
 }
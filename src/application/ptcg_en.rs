@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use tracing::info;
+
+use crate::{
+    domain::ReviewStatus,
+    error::Error,
+    matching::{self, MAX_LINKS_PER_CARD, MIN_EXPANSION_SCORE, MIN_LINK_SCORE},
+    repository::Repository,
+    scraper::tcg_collector::{ReleaseWindow, TcgCollectorRegion, TcgCollectorScraper},
+};
+
+pub struct PtcgEn {
+    pub scraper: TcgCollectorScraper,
+    pub repository: Repository,
+}
+
+impl PtcgEn {
+    pub async fn update_exp(&self, window: ReleaseWindow) -> Result<(), Error> {
+        let exps = self
+            .scraper
+            .fetch_exps(TcgCollectorRegion::Intl, window)
+            .await?;
+        self.repository.save_ptcg_en_expansions(exps).await?;
+        Ok(())
+    }
+    pub async fn update_cards(&self, window: ReleaseWindow) -> Result<(), Error> {
+        let links = self.repository.get_ptcg_en_expansions_links(window).await?;
+        for link in links {
+            let cards = self.scraper.fetch_card_list(&link).await?;
+            self.repository.save_ptcg_en_cards(cards).await?;
+        }
+        self.suggest_expansion_map().await?;
+        Ok(())
+    }
+    /// Sets the EN expansion of each mapped JP expansion to the most similar
+    /// one, leaving EN codes set by hand alone.
+    pub async fn suggest_expansion_map(&self) -> Result<(), Error> {
+        let jp_exps = self.repository.get_mapped_jp_expansion_names().await?;
+        let en_exps = self.repository.get_en_expansion_names().await?;
+        let mut suggested = 0;
+        for jp in &jp_exps {
+            let best = en_exps
+                .iter()
+                .map(|en| (en, matching::en_expansion_score(jp, en)))
+                .filter(|(_, score)| *score >= MIN_EXPANSION_SCORE)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((en, score)) = best {
+                self.repository
+                    .suggest_en_code(&jp.code, &en.code, score)
+                    .await?;
+                suggested += 1;
+            }
+        }
+        info!("suggested {} of {} EN expansions", suggested, jp_exps.len());
+        Ok(())
+    }
+    /// Scores TW printings without an English name against the cards of the
+    /// EN expansion mapped to theirs, keeping the best few for review. EN sets
+    /// are often combined and renumbered, so every candidate shares the
+    /// expansion and a matching number says little: a link also needs the
+    /// name of the linked JP printing or the rarity to match.
+    pub async fn build_name_links(&self) -> Result<(), Error> {
+        let printings = self.repository.get_en_name_printings().await?;
+        let mut en_cards = HashMap::new();
+        for (code, tw) in &printings {
+            if !en_cards.contains_key(&tw.expansion_code) {
+                let cards = self
+                    .repository
+                    .get_ptcg_en_card_features(&tw.expansion_code)
+                    .await?;
+                en_cards.insert(tw.expansion_code.clone(), cards);
+            }
+            let mut links: Vec<_> = en_cards[&tw.expansion_code]
+                .iter()
+                .map(|(url, en)| (url, matching::score(en, tw)))
+                .filter(|(_, link)| link.score >= MIN_LINK_SCORE)
+                .filter(|(_, link)| link.signals.iter().any(|s| *s == "name" || *s == "rarity"))
+                .collect();
+            links.sort_by(|a, b| b.1.score.total_cmp(&a.1.score));
+            for (url, link) in links.iter().take(MAX_LINKS_PER_CARD) {
+                self.repository.upsert_en_name_link(url, code, link).await?;
+            }
+        }
+        Ok(())
+    }
+    pub async fn export_name_links<W: std::io::Write>(
+        &self,
+        status: ReviewStatus,
+        w: W,
+    ) -> Result<(), Error> {
+        let mut wtr = csv::Writer::from_writer(w);
+        for link in self.repository.get_en_name_links(status).await? {
+            wtr.serialize(link)?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+    pub async fn approve_name_links(&self, ids: &[i64]) -> Result<(), Error> {
+        let named = self.repository.approve_en_name_links(ids).await?;
+        info!("named {} TW printings from EN cards", named);
+        Ok(())
+    }
+    pub async fn reject_name_links(&self, ids: &[i64]) -> Result<(), Error> {
+        let rejected = self.repository.reject_en_name_links(ids).await?;
+        info!("rejected {} EN name links", rejected);
+        Ok(())
+    }
+}
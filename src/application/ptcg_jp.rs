@@ -1,6 +1,19 @@
-use futures::TryStreamExt;
+use std::collections::HashSet;
 
-use crate::{error::Error, repository::Repository, scraper::tcg_collector::TcgCollectorScraper};
+use tracing::{info, warn};
+
+use crate::{
+    domain::{PtcgExtraSource, ReviewStatus},
+    error::Error,
+    matching::{
+        self, AttackFeature, CardFeatures, MAX_LINKS_PER_CARD, MIN_EXPANSION_SCORE, MIN_LINK_SCORE,
+    },
+    repository::Repository,
+    scraper::{
+        ptcg::PtcgRegion,
+        tcg_collector::{self, PtcgJpCard, ReleaseWindow, TcgCollectorRegion, TcgCollectorScraper},
+    },
+};
 
 pub struct PtcgJp {
     pub scraper: TcgCollectorScraper,
@@ -8,53 +21,283 @@ pub struct PtcgJp {
 }
 
 impl PtcgJp {
-    pub async fn update_exp(&self) -> Result<(), Error> {
-        let exps = self.scraper.fetch_exps().await?;
+    pub async fn update_exp(&self, window: ReleaseWindow) -> Result<(), Error> {
+        let exps = self
+            .scraper
+            .fetch_exps(TcgCollectorRegion::Jp, window)
+            .await?;
         self.repository.save_ptcg_jp_expansions(exps).await?;
+        self.suggest_expansion_map().await?;
         Ok(())
     }
-    pub async fn save_html(&self) -> Result<(), Error> {
-        let links = self.repository.get_ptcg_jp_expansions_links().await?;
-        for link in links {
-            let details = self.scraper.fetch_card_detail_html(&link).await?;
-            self.repository.save_tcg_collector(details).await?;
+    /// Maps each JP expansion to its most similar TW expansion, leaving
+    /// manual mappings alone.
+    pub async fn suggest_expansion_map(&self) -> Result<(), Error> {
+        let jp_exps = self.repository.get_jp_expansion_features().await?;
+        let tw_exps = self
+            .repository
+            .get_expansion_features(PtcgRegion::Tw)
+            .await?;
+        let mut suggested = 0;
+        for jp in &jp_exps {
+            let best = tw_exps
+                .iter()
+                .map(|tw| (tw, matching::expansion_score(jp, tw)))
+                .filter(|(_, score)| *score >= MIN_EXPANSION_SCORE)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((tw, score)) = best {
+                self.repository
+                    .suggest_expansion_mapping(PtcgRegion::Tw, &jp.code, &tw.code, score)
+                    .await?;
+                suggested += 1;
+            }
         }
+        info!(
+            "suggested {} of {} expansion mappings",
+            suggested,
+            jp_exps.len()
+        );
         Ok(())
     }
-    pub async fn update_cards(&self) -> Result<(), Error> {
+    pub async fn set_expansion_mapping(
+        &self,
+        region: PtcgRegion,
+        jp_code: &str,
+        tw_code: &str,
+        en_code: Option<&str>,
+    ) -> Result<(), Error> {
         self.repository
-            .get_tc_details()
-            .map_err(Error::from)
-            .try_for_each(|d| async move {
-                let card = self.scraper.fetch_card_detail(d).await?;
-                self.repository.save_ptcg_jp_cards(vec![card]).await?;
-                Ok(())
-            })
+            .set_expansion_mapping(region, jp_code, tw_code, en_code)
             .await?;
         Ok(())
     }
+    pub async fn export_expansion_map<W: std::io::Write>(
+        &self,
+        region: PtcgRegion,
+        w: W,
+    ) -> Result<(), Error> {
+        let mut wtr = csv::Writer::from_writer(w);
+        for mapping in self.repository.get_expansion_map(region).await? {
+            wtr.serialize(mapping)?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+    pub async fn save_tc_cards(&self, window: ReleaseWindow) -> Result<(), Error> {
+        let links = self.repository.get_ptcg_jp_expansions_links(window).await?;
+        for link in links {
+            let cards = self.scraper.fetch_cards(&link).await?;
+            self.repository.save_tcg_collector(cards).await?;
+        }
+        Ok(())
+    }
+    /// Parses detail pages stored before details were parsed on ingest.
+    pub async fn backfill_tc_details(&self) -> Result<(), Error> {
+        let pages = self.repository.get_unparsed_tcg_collector().await?;
+        for (url, html) in &pages {
+            let detail = tcg_collector::parse_card_detail(html);
+            self.repository
+                .save_tcg_collector_detail(url, &detail)
+                .await?;
+        }
+        info!("parsed {} stored tcgcollector pages", pages.len());
+        Ok(())
+    }
+    pub async fn update_cards(&self) -> Result<(), Error> {
+        let cards = self.repository.get_ptcg_jp_cards().await?;
+        self.repository.save_ptcg_jp_cards(cards).await?;
+        Ok(())
+    }
     pub async fn update_rarity(&self) -> Result<(), Error> {
-        let links = self.repository.get_ptcg_jp_expansions_links().await?;
+        let links = self
+            .repository
+            .get_ptcg_jp_expansions_links(ReleaseWindow::default())
+            .await?;
         for link in links {
             let rarities = self.scraper.fetch_card_rarity(&link).await?;
             self.repository.update_tc_rarity(rarities).await?;
         }
         Ok(())
     }
+    /// Proposes TW-equivalent rows for JP printings that have no TW printing,
+    /// matched to the most similar TW card of the same English name.
+    /// Proposes TW-equivalent rows for JP printings missing from TW. JP
+    /// expansions without a TW mapping are skipped, as their rows would have
+    /// no expansion to export under.
     pub async fn build_extra(&self) -> Result<(), Error> {
-        self.repository
-            .get_tc_details()
-            .map_err(Error::from)
-            .try_for_each(|d| async move {
-                if !self.repository.ptcg_tw_is_exists(&d).await? {
-                    let card = self.scraper.fetch_card_detail(d).await?;
-                    dbg!(&card);
-                    // self.repository.save_ptcg_jp_cards(vec![card]).await?;
-                    self.repository.save_extra(card).await?;
-                }
-                Ok(())
-            })
-            .await?;
+        let mut unmapped = HashSet::new();
+        for card in self.repository.get_ptcg_jp_cards().await? {
+            let Some(tw_exp_code) = self
+                .repository
+                .get_tw_expansion_code(&card.exp_code)
+                .await?
+            else {
+                unmapped.insert(card.exp_code);
+                continue;
+            };
+            if self
+                .repository
+                .ptcg_tw_is_exists(&card.number, &tw_exp_code)
+                .await?
+            {
+                continue;
+            }
+            let sources = self.repository.get_extra_sources(&card.name).await?;
+            let best = sources
+                .iter()
+                .map(|source| (source, extra_confidence(&card, source)))
+                .filter(|(_, confidence)| *confidence > NAME_CONFIDENCE)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((source, confidence)) = best {
+                self.repository
+                    .upsert_extra_candidate(&card, &tw_exp_code, source, confidence)
+                    .await?;
+            }
+        }
+        for exp_code in unmapped {
+            warn!("jp expansion {exp_code} has no tw mapping, its extras skipped");
+        }
+        Ok(())
+    }
+    /// Scores every JP printing against the TW printings it could be, keeping
+    /// the best few candidates for review.
+    pub async fn build_links(&self) -> Result<(), Error> {
+        for card in self.repository.get_ptcg_jp_cards().await? {
+            let tw_exp_code = self
+                .repository
+                .get_tw_expansion_code(&card.exp_code)
+                .await?;
+            let jp = CardFeatures {
+                // compared against TW codes, so use the mapped one when known
+                expansion_code: tw_exp_code.unwrap_or_else(|| card.exp_code.clone()),
+                number: card.number.clone(),
+                name: Some(card.name.clone()),
+                rarity: card.rarity.as_ref().map(|r| r.to_string()),
+                hp: card.hp.clone(),
+                attacks: card.attacks.iter().map(AttackFeature::from).collect(),
+            };
+            let candidates = self
+                .repository
+                .get_link_candidates(&card.exp_code, &card.name)
+                .await?;
+            let mut links: Vec<_> = candidates
+                .iter()
+                .map(|(code, tw)| (code, matching::score(&jp, tw)))
+                .filter(|(_, link)| link.score >= MIN_LINK_SCORE)
+                .collect();
+            links.sort_by(|a, b| b.1.score.total_cmp(&a.1.score));
+            for (code, link) in links.iter().take(MAX_LINKS_PER_CARD) {
+                self.repository
+                    .upsert_card_link(&card.url, code, link)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+    pub async fn export_card_links<W: std::io::Write>(
+        &self,
+        status: ReviewStatus,
+        w: W,
+    ) -> Result<(), Error> {
+        let mut wtr = csv::Writer::from_writer(w);
+        for link in self.repository.get_card_links(status).await? {
+            wtr.serialize(link)?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+    pub async fn approve_links(&self, ids: &[i64]) -> Result<(), Error> {
+        let approved = self.repository.approve_card_links(ids).await?;
+        info!("approved {} card links", approved);
+        Ok(())
+    }
+    pub async fn reject_links(&self, ids: &[i64]) -> Result<(), Error> {
+        let rejected = self.repository.reject_card_links(ids).await?;
+        info!("rejected {} card links", rejected);
+        Ok(())
+    }
+    pub async fn export_extra_candidates<W: std::io::Write>(
+        &self,
+        status: ReviewStatus,
+        w: W,
+    ) -> Result<(), Error> {
+        let mut wtr = csv::Writer::from_writer(w);
+        for candidate in self.repository.get_extra_candidates(status).await? {
+            wtr.serialize(candidate)?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+    pub async fn approve_extra(&self, ids: &[i64]) -> Result<(), Error> {
+        let approved = self.repository.approve_extra_candidates(ids).await?;
+        info!("approved {} extra printings", approved.len());
+        for id in ids.iter().filter(|id| !approved.contains(id)) {
+            warn!(
+                "extra candidate {id} not approved: not pending, its printing already exists or its expansion is not a tw expansion"
+            );
+        }
+        Ok(())
+    }
+    pub async fn reject_extra(&self, ids: &[i64]) -> Result<(), Error> {
+        let rejected = self.repository.reject_extra_candidates(ids).await?;
+        info!("rejected {} extra candidates", rejected);
         Ok(())
     }
 }
+
+/// Candidates are only looked up by English name, so a name match alone is
+/// worth this much and is not enough to propose a match.
+const NAME_CONFIDENCE: f32 = 0.4;
+
+fn extra_confidence(card: &PtcgJpCard, source: &PtcgExtraSource) -> f32 {
+    let same = |a: &Option<String>, b: &Option<String>| a.is_some() && a == b;
+    let mut confidence = NAME_CONFIDENCE;
+    if same(&card.skill1_name_en, &source.skill1_name_en) {
+        confidence += 0.3;
+    }
+    if same(&card.skill1_damage, &source.skill1_damage) {
+        confidence += 0.1;
+    }
+    if same(&card.desc, &source.card_description_en) {
+        confidence += 0.2;
+    }
+    confidence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jp_card() -> PtcgJpCard {
+        PtcgJpCard {
+            name: "Pikachu".to_string(),
+            number: "120/100".to_string(),
+            exp_code: "sv5k".to_string(),
+            desc: None,
+            hp: None,
+            skill1_name_en: Some("Thunder Jolt".to_string()),
+            skill1_damage: Some("30".to_string()),
+            attacks: vec![],
+            url: "https://www.tcgcollector.com/cards/1".to_string(),
+            rarity: None,
+        }
+    }
+
+    #[test]
+    fn attack_match_scores_above_name_only() {
+        let card = jp_card();
+        let source = PtcgExtraSource {
+            code: "1".to_string(),
+            skill1_name_en: Some("Thunder Jolt".to_string()),
+            skill1_damage: Some("30".to_string()),
+            card_description_en: None,
+        };
+        assert!((extra_confidence(&card, &source) - 0.8).abs() < f32::EPSILON);
+        let other = PtcgExtraSource {
+            skill1_name_en: Some("Quick Attack".to_string()),
+            skill1_damage: Some("10".to_string()),
+            ..source
+        };
+        assert_eq!(extra_confidence(&card, &other), NAME_CONFIDENCE);
+    }
+}
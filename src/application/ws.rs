@@ -1,12 +1,21 @@
 use futures::TryStreamExt;
 use google_cloud_storage::client::ClientConfig;
+use tracing::{info, warn};
 
 use crate::{
-    error::Error, export::export_csv::ExportCsv, repository::Repository, scraper::ws::WsScraper,
+    domain::Language,
+    error::Error,
+    export::export_csv::ExportCsv,
+    repository::Repository,
+    scraper::ws::{WsRegion, WsScraper},
 };
 
 use super::GcsDownloader;
 
+/// Pages walked per set when the site shows no page count; far more than any
+/// set has, so reaching it means the `expansion` filter was ignored.
+const MAX_SET_PAGES: i32 = 100;
+
 pub struct Ws {
     pub scraper: WsScraper,
     pub repository: Repository,
@@ -25,7 +34,11 @@ impl Ws {
         stream
             .map_err(Error::from)
             .try_for_each(|c| async move {
-                let image_url = url::Url::parse(&format!("https://ws-tcg.com{}", c.img_src))?;
+                let region = match c.language {
+                    Language::Ja => WsRegion::Jp,
+                    Language::En => WsRegion::En,
+                };
+                let image_url = url::Url::parse(&format!("{}{}", region.base_url(), c.img_src))?;
                 downloader.download(image_url).await?;
                 Ok(())
             })
@@ -33,17 +46,79 @@ impl Ws {
         Ok(())
     }
     pub async fn scrape(&self) -> Result<(), Error> {
+        let region = self.scraper.region;
         let total_pages = self.scraper.get_total_pages().await?;
-        let progress = self.repository.get_ws_progress().await?;
-        for n in progress + 1..=total_pages {
+        let progress = self.repository.get_ws_progress(region).await?;
+        let mut last_page_first_code = None;
+        for n in progress + 1..=total_pages.unwrap_or(i32::MAX) {
             let cards = self.scraper.scrape_by_page(n).await?;
-            let cards = cards.into_iter().filter_map(|s| s.ok()).collect();
+            // Without a page count, stop where the pages run out.
+            if total_pages.is_none() {
+                let first_code = cards.first().map(|c| c.code.clone());
+                if is_past_last_page(&first_code, &last_page_first_code) {
+                    break;
+                }
+                last_page_first_code = first_code;
+            }
             self.repository.save_ws_cards(cards).await?;
-            self.repository.update_ws_progress(n + 1).await?;
+            self.repository.update_ws_progress(region, n + 1).await?;
         }
         Ok(())
     }
 
+    /// Re-scrape the newest `latest` sets and any set left unfinished by an
+    /// earlier run, instead of walking every search page. Each set is walked
+    /// up to its page count, or [`MAX_SET_PAGES`] where none is shown; a set
+    /// that runs into the cap stays unfinished.
+    pub async fn sync(&self, latest: i64) -> Result<(), Error> {
+        let region = self.scraper.region;
+        let expansions = self.scraper.fetch_expansions().await?;
+        self.repository.upsert_ws_sets(region, &expansions).await?;
+        for set in self.repository.get_ws_sets_to_sync(region, latest).await? {
+            info!("syncing ws set {} {}", set.id, set.name);
+            let total_pages = self.scraper.get_expansion_total_pages(&set.id).await?;
+            let mut card_count = 0;
+            let mut last_page_first_code = None;
+            let mut reached_end = total_pages.is_some();
+            for n in 1..=total_pages.unwrap_or(MAX_SET_PAGES) {
+                let cards = self.scraper.scrape_expansion_page(&set.id, n).await?;
+                let first_code = cards.first().map(|c| c.code.clone());
+                if is_past_last_page(&first_code, &last_page_first_code) {
+                    reached_end = true;
+                    break;
+                }
+                last_page_first_code = first_code;
+                card_count += cards.len() as i32;
+                self.repository.save_ws_cards(cards).await?;
+            }
+            if !reached_end {
+                warn!(
+                    "ws set {} {} still had cards after {} pages, left unfinished",
+                    set.id, set.name, MAX_SET_PAGES
+                );
+                continue;
+            }
+            self.repository
+                .complete_ws_set(region, &set.id, card_count)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn scrape_details(&self) -> Result<(), Error> {
+        self.repository.enqueue_ws_card_details().await?;
+        self.repository
+            .get_ws_detail_fetchable(self.scraper.region.language())
+            .map_err(Error::from)
+            .try_for_each(|code| async move {
+                let detail = self.scraper.scrape_card_detail(&code).await?;
+                self.repository.save_ws_card_detail(detail).await?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
     pub async fn export_csv<W: std::io::Write>(&self, w: W) -> Result<(), Error> {
         let mut wtr = csv::Writer::from_writer(w);
         let mut s = self.repository.get_ws_cards();
@@ -55,3 +130,8 @@ impl Ws {
         Ok(())
     }
 }
+
+// Past the last page the site either shows nothing or repeats the last page.
+fn is_past_last_page(first_code: &Option<String>, last_page_first_code: &Option<String>) -> bool {
+    first_code.is_none() || first_code == last_page_first_code
+}
@@ -38,6 +38,7 @@ impl Yugioh {
             self.repository
                 .upsert_yugioh_printing_detail(printing)
                 .await
+                .unwrap();
         }
         link.done().await;
         Some(())
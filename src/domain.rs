@@ -1,5 +1,6 @@
+use chrono::NaiveDate;
 use derive_builder::Builder;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use sqlx::{
     postgres::{PgHasArrayType, PgTypeInfo},
     types::time::OffsetDateTime,
@@ -7,6 +8,8 @@ use sqlx::{
 use strum::EnumString;
 use time::macros::format_description;
 
+use crate::scraper::ptcg::PtcgRegion;
+
 #[derive(Debug, Clone)]
 pub struct LastFetchedAt {
     pub inner: OffsetDateTime,
@@ -22,6 +25,12 @@ impl LastFetchedAt {
     }
 }
 
+impl From<OffsetDateTime> for LastFetchedAt {
+    fn from(inner: OffsetDateTime) -> Self {
+        Self { inner }
+    }
+}
+
 impl Default for LastFetchedAt {
     fn default() -> Self {
         Self {
@@ -30,18 +39,207 @@ impl Default for LastFetchedAt {
     }
 }
 
+/// A field of a scraped card that an upsert overwrote with a different value.
+#[derive(Debug, Serialize)]
+pub struct CardRevision {
+    /// table the card lives in, e.g. `pokemon_trainer_printing`
+    pub source: String,
+    pub card_key: String,
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_at: String,
+}
+
+/// A TW printing that a JP-only printing may be another version of.
+#[derive(Debug)]
+pub struct PtcgExtraSource {
+    pub code: String,
+    pub skill1_name_en: Option<String>,
+    pub skill1_damage: Option<String>,
+    pub card_description_en: Option<String>,
+}
+
+#[derive(EnumString, strum::Display, Clone, Copy, Debug, PartialEq, Default)]
+#[strum(serialize_all = "lowercase")]
+pub enum ReviewStatus {
+    #[default]
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// A proposed TW-equivalent row for a JP-only printing, waiting for review.
+#[derive(Debug, Serialize)]
+pub struct PtcgExtraCandidate {
+    pub id: i64,
+    /// code the row gets in `pokemon_trainer_printing` once approved
+    pub code: String,
+    pub source_code: String,
+    pub source_url: String,
+    pub name_en: String,
+    pub number: String,
+    pub expansion_code: String,
+    pub confidence: f32,
+    pub status: String,
+}
+
+/// Which TW (and EN) expansion a tcgcollector JP expansion corresponds to.
+#[derive(Debug, Serialize)]
+pub struct PtcgExpansionMapping {
+    pub region: String,
+    pub jp_code: String,
+    pub jp_name: String,
+    pub tw_code: String,
+    pub tw_name: String,
+    pub en_code: Option<String>,
+    pub score: Option<f32>,
+    pub source: String,
+    /// `None` when `en_code` was set by hand
+    pub en_score: Option<f32>,
+}
+
+/// A printing a marketplace listing could be, from any game.
+#[derive(Debug, PartialEq)]
+pub struct ListingCandidate {
+    /// `ptcg`, `one_piece` or `ws`
+    pub game: String,
+    pub id: String,
+    pub name: String,
+    /// English name, where the catalogue has one
+    pub alt_name: Option<String>,
+    pub rarity: Option<String>,
+}
+
+/// One output row of listing matching.
+#[derive(Debug, Serialize)]
+pub struct ListingMatchRow {
+    pub title: String,
+    pub game: Option<String>,
+    pub card_id: Option<String>,
+    pub card_name: Option<String>,
+    pub confidence: f32,
+    pub reasons: String,
+}
+
+/// One observed price from `card_prices`, at day resolution.
+#[derive(Debug, Clone)]
+pub struct PricePoint {
+    pub game: String,
+    pub set_code: String,
+    pub source: String,
+    pub card_key: String,
+    pub name: String,
+    pub price: i64,
+    pub observed_on: NaiveDate,
+}
+
+/// One card of the price report, as of its latest observation.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct PriceReportRow {
+    pub game: String,
+    pub set_code: String,
+    pub source: String,
+    pub card_key: String,
+    pub name: String,
+    pub observed_on: String,
+    pub price: i64,
+    pub ma7: f64,
+    pub ma30: f64,
+    /// against the last price at least 7 days older, when there is one
+    pub change_pct: Option<f64>,
+    /// distance of the price from the previous 30 days, in standard deviations
+    pub z_score: Option<f64>,
+    pub anomaly: bool,
+}
+
+#[derive(EnumString, strum::Display, Clone, Copy, Debug, PartialEq, Default)]
+#[strum(serialize_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+/// A scored guess that a tcgcollector (JP) card is a TW printing.
+#[derive(Debug, Serialize)]
+pub struct PtcgCardLink {
+    pub id: i64,
+    pub tc_url: String,
+    pub tc_name: Option<String>,
+    pub tc_number: Option<String>,
+    pub tc_exp_code: Option<String>,
+    pub printing_code: String,
+    pub printing_name: Option<String>,
+    pub printing_number: Option<String>,
+    pub printing_expansion_code: Option<String>,
+    pub score: f32,
+    pub signals: String,
+    pub status: String,
+}
+
+/// A scored guess that a tcgcollector EN card is a TW printing, whose English
+/// name it gives once approved.
+#[derive(Debug, Serialize)]
+pub struct PtcgEnNameLink {
+    pub id: i64,
+    pub en_url: String,
+    pub en_name: Option<String>,
+    pub en_number: Option<String>,
+    pub en_exp_code: Option<String>,
+    pub printing_code: String,
+    pub printing_name: Option<String>,
+    pub printing_number: Option<String>,
+    pub printing_expansion_code: Option<String>,
+    pub score: f32,
+    pub signals: String,
+    pub status: String,
+}
+
+#[derive(EnumString, strum::Display, Clone, Copy, Debug, PartialEq, Default)]
+#[strum(serialize_all = "lowercase")]
+pub enum Language {
+    #[default]
+    Ja,
+    En,
+}
+
 #[derive(Builder, Default, Debug)]
 pub struct PokemonCard {
     pub id: String,
     pub set_id: String,
     pub set_name: String,
     pub set_ref: String,
+    pub region: PtcgRegion,
+    /// as printed, in the region's language
     pub name: String,
     pub number: Option<String>,
     pub sale_price: Option<i64>,
     pub rarity: Option<String>,
     pub last_fetched_at: LastFetchedAt,
     pub remark: Option<String>,
+    pub hp: Option<String>,
+    pub energy_type: Option<String>,
+    pub artist: Option<String>,
+    /// from the 52poke wiki, when the printing is listed there
+    pub name_chinese: Option<String>,
+    /// from an approved EN name link
+    pub name_en: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PtcgAttack {
+    pub name: String,
+    /// Energy type names, one entry per energy symbol.
+    pub cost: Vec<String>,
+    pub damage: Option<String>,
+    pub effect: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PtcgAbility {
+    pub name: String,
+    pub effect: Option<String>,
 }
 
 #[allow(clippy::upper_case_acronyms)]
@@ -23,8 +23,3 @@ pub(crate) enum Error {
     #[error("csv error {0}")]
     Csv(#[from] csv::Error),
 }
-
-#[derive(Debug)]
-pub enum ErrorCode {
-    RarityNotExists,
-}
@@ -1,4 +1,5 @@
 use crate::{
+    domain::Language,
     export::export_csv::ExportCsv,
     scraper::one_piece::{OnePieceCard, OnePieceProduct},
 };
@@ -28,19 +29,23 @@ impl From<OnePieceCard> for ExportCsv {
         let (set_code, _card_number) = code.split_once('-').unwrap();
         let reference = Some(set_code.to_owned());
         let remark9 = Some(set_code.to_owned());
+        let (card_name_english, card_name_japanese) = match value.language {
+            Language::Ja => (value.counterpart_name, Some(value.name)),
+            Language::En => (Some(value.name), value.counterpart_name),
+        };
         Self {
             product_id: None,
             brand: Some(String::from("One Piece")),
             set: Some(sanitize(&value.set_name)),
             edition: None,
             series: None,
-            rarity: Some(value.rarity.as_ref().to_string()),
+            rarity: Some(value.rarity.to_string()),
             material: None,
             release_year: None,
-            language: Some(String::from("ja")),
-            card_name_english: None,
+            language: Some(value.language.to_string()),
+            card_name_english,
             card_name_chinese: None,
-            card_name_japanese: Some(value.name),
+            card_name_japanese,
             card_number: Some(value.code.clone()),
             image: Some(value.img_src),
             value: None,
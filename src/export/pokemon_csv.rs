@@ -1,9 +1,16 @@
-use crate::{domain::PokemonCard, export::export_csv::ExportCsv};
+use crate::{domain::PokemonCard, export::export_csv::ExportCsv, scraper::ptcg::PtcgRegion};
 use lazy_static::lazy_static;
 use regex::Regex;
 
 impl From<PokemonCard> for ExportCsv {
     fn from(value: PokemonCard) -> Self {
+        // The printed name goes in the column of its language. Thai and
+        // Indonesian have none, so theirs go in remark8 and the 52poke name
+        // fills the Chinese column instead.
+        let (card_name_chinese, remark8) = match value.region {
+            PtcgRegion::Tw | PtcgRegion::Hk => (Some(value.name), None),
+            PtcgRegion::Th | PtcgRegion::Id => (value.name_chinese, Some(value.name)),
+        };
         ExportCsv {
             product_id: None,
             brand: Some(String::from("Pokemon")),
@@ -13,10 +20,10 @@ impl From<PokemonCard> for ExportCsv {
             rarity: value.rarity,
             material: None,
             release_year: None,
-            language: Some(String::from("ja")),
-            card_name_english: None,
-            card_name_chinese: None,
-            card_name_japanese: Some(value.name),
+            language: Some(value.region.language().to_string()),
+            card_name_english: value.name_en,
+            card_name_chinese,
+            card_name_japanese: None,
             card_number: value.number,
             image: Some(format!("{}.jpg", value.id)),
             value: value.sale_price.map(|p| p.to_string()),
@@ -26,10 +33,10 @@ impl From<PokemonCard> for ExportCsv {
             remark2: value.last_fetched_at.created_datetime(),
             remark3: Some(value.set_id),
             remark4: Some(value.id),
-            remark5: None,
-            remark6: None,
-            remark7: None,
-            remark8: None,
+            remark5: value.hp,
+            remark6: value.energy_type,
+            remark7: value.artist,
+            remark8,
             remark9: value.remark,
             remark10: None,
             enable: None,
@@ -50,6 +57,26 @@ fn sanitize(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    fn card(region: PtcgRegion, name: &str) -> PokemonCard {
+        PokemonCard {
+            region,
+            name: name.to_string(),
+            name_chinese: Some("皮卡丘".to_string()),
+            ..Default::default()
+        }
+    }
+    #[test]
+    fn name_column_follows_region() {
+        let row: ExportCsv = card(PtcgRegion::Tw, "皮卡丘").into();
+        assert_eq!(row.language.as_deref(), Some("zh-tw"));
+        assert_eq!(row.card_name_chinese.as_deref(), Some("皮卡丘"));
+        assert_eq!(row.card_name_japanese, None);
+        let row: ExportCsv = card(PtcgRegion::Th, "พิคาชู").into();
+        assert_eq!(row.language.as_deref(), Some("th"));
+        assert_eq!(row.card_name_chinese.as_deref(), Some("皮卡丘"));
+        assert_eq!(row.remark8.as_deref(), Some("พิคาชู"));
+        assert_eq!(row.card_name_japanese, None);
+    }
     #[test]
     fn sanitize_x_kind() {
         let result = sanitize("バトル強化デッキ 3種");
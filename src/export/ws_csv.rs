@@ -1,9 +1,13 @@
-use crate::{export::export_csv::ExportCsv, scraper::ws::WsCard};
+use crate::{domain::Language, export::export_csv::ExportCsv, scraper::ws::WsCard};
 use lazy_static::lazy_static;
 use regex::Regex;
 
 impl From<WsCard> for ExportCsv {
     fn from(value: WsCard) -> Self {
+        let (card_name_english, card_name_japanese) = match value.language {
+            Language::Ja => (value.counterpart_name, Some(value.name)),
+            Language::En => (Some(value.name), value.counterpart_name),
+        };
         ExportCsv {
             product_id: None,
             brand: Some(String::from("Weiβ Schwarz")),
@@ -13,10 +17,10 @@ impl From<WsCard> for ExportCsv {
             rarity: value.rarity,
             material: None,
             release_year: None,
-            language: Some(String::from("ja")),
-            card_name_english: None,
+            language: Some(value.language.to_string()),
+            card_name_english,
             card_name_chinese: None,
-            card_name_japanese: Some(value.name),
+            card_name_japanese,
             card_number: Some(value.code.clone()),
             image: Some(value.img_src),
             value: None,
@@ -1,10 +1,7 @@
 use crate::{export::export_csv::ExportCsv, scraper::yugioh::YugiohPrinting};
-use time::macros::format_description;
 
 impl From<YugiohPrinting> for ExportCsv {
     fn from(value: YugiohPrinting) -> Self {
-        let now = time::OffsetDateTime::now_utc();
-        let format = format_description!("[day]/[month]/[year] [hour]:[minute]");
         Self {
             product_id: None,
             brand: Some(String::from("Yu-Gi-Oh!")),
@@ -23,8 +20,8 @@ impl From<YugiohPrinting> for ExportCsv {
             value: None,
             reference: Some(value.r#ref),
             remark: None,
-            remark1: Some(now.unix_timestamp().to_string()),
-            remark2: Some(now.format(format).unwrap()),
+            remark1: Some(value.last_fetched_at.inner.unix_timestamp().to_string()),
+            remark2: value.last_fetched_at.created_datetime(),
             remark3: None,
             remark4: None,
             remark5: Some(value.remark),
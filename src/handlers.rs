@@ -1,5 +1,3 @@
-use std::fmt::Display;
-
 use axum::{
     extract::{Query, State},
     response::IntoResponse,
@@ -10,7 +8,10 @@ use reqwest::{header::CONTENT_TYPE, StatusCode};
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Postgres};
 
-use crate::application::ptcg::Ptcg;
+use crate::{
+    application::ptcg::Ptcg,
+    scraper::ptcg::{energy_type, PtcgRegion},
+};
 
 #[derive(Clone)]
 pub struct MyState {
@@ -84,7 +85,14 @@ pub async fn search(param: Query<SearchParam>, state: State<MyState>) -> Result<
     })
 }
 
-pub async fn pokemon() -> Result<Markup, Error> {
+/// Pages show one region's cards; expansion codes repeat across regions.
+#[derive(Deserialize)]
+pub struct RegionQuery {
+    #[serde(default)]
+    region: PtcgRegion,
+}
+
+pub async fn pokemon(query: Query<RegionQuery>) -> Result<Markup, Error> {
     Ok(html! {
         (DOCTYPE)
         script src="https://unpkg.com/htmx.org@1.9.10" {}
@@ -97,7 +105,7 @@ pub async fn pokemon() -> Result<Markup, Error> {
                     .large.title {}
                     #list {}
                 }
-                section.flex.row.wrap.quater.screen-v-scroll.noselect hx-get="/explist" hx-trigger="load" hx-target="#explist" {
+                section.flex.row.wrap.quater.screen-v-scroll.noselect hx-get={ (format!("/explist?region={}", query.region)) } hx-trigger="load" hx-target="#explist" {
                     #explist {}
                 }
             }
@@ -108,6 +116,8 @@ pub async fn pokemon() -> Result<Markup, Error> {
 #[derive(Deserialize)]
 pub struct ListQuery {
     code: String,
+    #[serde(default)]
+    region: PtcgRegion,
 }
 pub async fn list(query: Query<ListQuery>, state: State<MyState>) -> Result<Markup, Error> {
     let cards = sqlx::query!(
@@ -117,8 +127,12 @@ pub async fn list(query: Query<ListQuery>, state: State<MyState>) -> Result<Mark
             COALESCE(ptp.number, wiki.number) AS "number!",
             COALESCE(ptp.expansion_code, wiki.exp_code) "exp_code!",
             COALESCE(ptp.rarity, wiki.rarity::TEXT) rarity,
-            ptp.code as "code?"
-        FROM pokemon_trainer_printing ptp
+            ptp.code as "code?",
+            ptp.region as "region?",
+            ptp.hp as "hp?",
+            ptp.energy as "energy?",
+            ptp.artist as "artist?"
+        FROM (SELECT * FROM pokemon_trainer_printing WHERE region = $2) ptp
         FULL JOIN pokewiki wiki
             ON LOWER(wiki.exp_code) = LOWER(ptp.expansion_code)
             AND wiki.name = ptp.name
@@ -127,7 +141,8 @@ pub async fn list(query: Query<ListQuery>, state: State<MyState>) -> Result<Mark
             LOWER(ptp.expansion_code) = LOWER($1) OR LOWER(wiki.exp_code) = LOWER($1)
         ORDER BY "number!"
         "#,
-        query.code
+        query.code,
+        query.region.to_string()
     )
     .fetch_all(&state.pool)
     .await?;
@@ -135,11 +150,14 @@ pub async fn list(query: Query<ListQuery>, state: State<MyState>) -> Result<Mark
         h1 { (query.code) }
         table #list {
             @for card in cards {
-                tr hx-get={ (format!("/modal?name={}&number={}&exp_code={}", card.name, card.number, card.exp_code)) } hx-target="body" hx-swap="beforeend" {
-                    td { img.table_img src={(format!("https://asia.pokemon-card.com/tw/card-img/tw{:08}.png", card.code.unwrap_or("0".to_string()).parse::<i32>().unwrap()))}; }
+                tr hx-get={ (format!("/modal?name={}&number={}&exp_code={}&region={}", card.name, card.number, card.exp_code, query.region)) } hx-target="body" hx-swap="beforeend" {
+                    td { img.table_img src={(card_image_url(card.region.as_deref(), card.code.as_deref()).unwrap_or_default())}; }
                     td { (card.name) }
                     td { (card.number) }
                     td { (card.rarity.unwrap_or("Unknown".to_string())) }
+                    td { (card.hp.unwrap_or_default()) }
+                    td { (card.energy.as_deref().and_then(energy_type).unwrap_or_default()) }
+                    td { (card.artist.unwrap_or_default()) }
                     td { (card.exp_code) }
                 }
             }
@@ -147,15 +165,21 @@ pub async fn list(query: Query<ListQuery>, state: State<MyState>) -> Result<Mark
     };
     Ok(markup)
 }
-pub async fn exp_list(state: State<MyState>) -> Result<Markup, Error> {
-    let exps =
-        sqlx::query!("SELECT code, name FROM pokemon_trainer_expansion ORDER BY release_date DESC")
-            .fetch_all(&state.pool)
-            .await?;
+fn card_image_url(region: Option<&str>, code: Option<&str>) -> Option<String> {
+    let region: PtcgRegion = region?.parse().ok()?;
+    Some(region.image_url(code?.parse().ok()?))
+}
+pub async fn exp_list(query: Query<RegionQuery>, state: State<MyState>) -> Result<Markup, Error> {
+    let exps = sqlx::query!(
+        "SELECT code, name FROM pokemon_trainer_expansion WHERE region = $1 ORDER BY release_date DESC",
+        query.region.to_string()
+    )
+    .fetch_all(&state.pool)
+    .await?;
     let markup = html! {
         .pad {
             @for exp in exps {
-                a.green href="#" _="on click take .selected from a.green for the event's target" hx-get={ (format!("/list?code={}", exp.code)) } hx-target="#list" {
+                a.green href="#" _="on click take .selected from a.green for the event's target" hx-get={ (format!("/list?code={}&region={}", exp.code, query.region)) } hx-target="#list" {
                     (format!("{:<5}:{}",exp.code, exp.name))
                 }
                 br;
@@ -170,6 +194,8 @@ pub struct ModalQuery {
     name: String,
     number: String,
     exp_code: String,
+    #[serde(default)]
+    region: PtcgRegion,
 }
 
 pub async fn modal(state: State<MyState>, query: Query<ModalQuery>) -> Result<Markup, Error> {
@@ -179,8 +205,11 @@ pub async fn modal(state: State<MyState>, query: Query<ModalQuery>) -> Result<Ma
             COALESCE(ptp.name, wiki.name) AS "name!",
             COALESCE(ptp.number, wiki.number) AS "number!",
             COALESCE(ptp.expansion_code, wiki.exp_code) "exp_code!",
-            COALESCE(ptp.rarity, wiki.rarity::TEXT) rarity
-        FROM pokemon_trainer_printing ptp
+            COALESCE(ptp.rarity, wiki.rarity::TEXT) rarity,
+            ptp.hp as "hp?",
+            ptp.energy as "energy?",
+            ptp.artist as "artist?"
+        FROM (SELECT * FROM pokemon_trainer_printing WHERE region = $4) ptp
         FULL JOIN pokewiki wiki
             ON LOWER(wiki.exp_code) = LOWER(ptp.expansion_code)
             AND wiki.name = ptp.name
@@ -194,7 +223,8 @@ pub async fn modal(state: State<MyState>, query: Query<ModalQuery>) -> Result<Ma
         "#,
         query.exp_code,
         query.name,
-        query.number
+        query.number,
+        query.region.to_string()
     )
     .fetch_one(&state.pool)
     .await?;
@@ -207,6 +237,15 @@ pub async fn modal(state: State<MyState>, query: Query<ModalQuery>) -> Result<Ma
                 h1 { "hihi" }
                 div { (card.name) }
                 div { (card.rarity.unwrap_or("Unknown".to_string())) }
+                @if let Some(hp) = card.hp {
+                    div { "HP " (hp) }
+                }
+                @if let Some(energy_type) = card.energy.as_deref().and_then(energy_type) {
+                    div { (energy_type) }
+                }
+                @if let Some(artist) = card.artist {
+                    div { "Illus. " (artist) }
+                }
                 div {
                     input value={(n)};
                     "/"
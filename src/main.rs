@@ -3,19 +3,26 @@ mod domain;
 mod error;
 mod export;
 mod handlers;
+mod matching;
+mod price_report;
 mod repository;
 mod scraper;
 mod strategy;
+mod title;
 
 use application::Application;
 use axum::{routing::get, Router};
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use color_eyre::eyre::Result;
+use domain::{ReportFormat, ReviewStatus};
 use handlers::exp_list;
 use meilisearch_sdk::client::Client;
-use serde::Deserialize;
+use scraper::{
+    one_piece::OnePieceRegion, ptcg::PtcgRegion, tcg_collector::ReleaseWindow, ws::WsRegion,
+};
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use std::{thread::sleep, time::Duration};
+use std::{collections::HashSet, path::PathBuf, thread::sleep, time::Duration};
 use strategy::Source;
 use tracing::{debug, info};
 
@@ -40,7 +47,37 @@ enum Commands {
     #[command(subcommand)]
     PtcgJp(PtcgJpCommands),
     #[command(subcommand)]
+    PtcgEn(PtcgEnCommands),
+    #[command(subcommand)]
+    Prices(PricesCommands),
+    #[command(subcommand)]
     Serve(ServeCommands),
+    /// card fields changed upstream since a date, as CSV on stdout
+    Revisions {
+        /// e.g. 2024-07-01
+        #[arg(long)]
+        since: chrono::NaiveDate,
+    },
+    /// match listing titles (CSV with a `title` column on stdin) to cards,
+    /// as CSV on stdout
+    MatchListings,
+}
+
+#[derive(Subcommand)]
+enum PricesCommands {
+    /// record current bigweb prices of Pokémon cards
+    Bigweb,
+    /// moving averages, top movers per game and set, and price anomalies
+    Report {
+        /// prices observed in this many days are considered
+        #[arg(long, default_value_t = 60)]
+        days: u64,
+        /// movers kept per game and set
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+        #[arg(long, default_value = "csv")]
+        format: ReportFormat,
+    },
 }
 
 #[derive(Subcommand)]
@@ -48,26 +85,164 @@ enum ServeCommands {
     Ptcg,
 }
 
+/// only expansions released in this window, bounds included
+#[derive(Args)]
+struct ReleaseWindowArgs {
+    #[arg(long)]
+    since: Option<chrono::NaiveDate>,
+    #[arg(long)]
+    until: Option<chrono::NaiveDate>,
+}
+
+impl From<&ReleaseWindowArgs> for ReleaseWindow {
+    fn from(value: &ReleaseWindowArgs) -> Self {
+        ReleaseWindow {
+            since: value.since,
+            until: value.until,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum PtcgEnCommands {
+    Exp {
+        #[command(flatten)]
+        window: ReleaseWindowArgs,
+    },
+    Card {
+        #[command(flatten)]
+        window: ReleaseWindowArgs,
+    },
+    /// re-suggest the EN expansion of mapped JP expansions; manual ones are
+    /// kept
+    ExpMap,
+    /// score unnamed TW printings against EN cards of the mapped EN expansion
+    /// into reviewable links
+    Names,
+    /// EN name links with the given status, as CSV on stdout
+    NameList {
+        #[arg(long, default_value = "pending")]
+        status: ReviewStatus,
+    },
+    NameApprove {
+        ids: Vec<i64>,
+    },
+    NameReject {
+        ids: Vec<i64>,
+    },
+}
+
 #[derive(Subcommand)]
 enum PtcgJpCommands {
-    Exp,
+    Exp {
+        #[command(flatten)]
+        window: ReleaseWindowArgs,
+    },
+    /// re-suggest JP to TW expansion mappings; manual ones are kept
+    ExpMap,
+    /// expansion mappings as CSV on stdout
+    ExpMapList {
+        #[arg(long, default_value = "tw")]
+        region: PtcgRegion,
+    },
+    /// map a JP expansion by hand, overriding any suggestion
+    ExpMapSet {
+        jp_code: String,
+        tw_code: String,
+        #[arg(long)]
+        en_code: Option<String>,
+        #[arg(long, default_value = "tw")]
+        region: PtcgRegion,
+    },
     Card,
-    Tc,
+    /// fetch and parse tcgcollector cards of every mapped expansion
+    Tc {
+        /// keep detail pages here and reuse them on later runs
+        #[arg(long)]
+        html_cache: Option<PathBuf>,
+        #[command(flatten)]
+        window: ReleaseWindowArgs,
+    },
+    /// parse tcgcollector detail pages stored before details were parsed on
+    /// ingest
+    TcBackfill,
+    /// propose TW-equivalent rows for JP-only printings
     Extra,
+    /// extra candidates with the given status, as CSV on stdout
+    ExtraList {
+        #[arg(long, default_value = "pending")]
+        status: ReviewStatus,
+    },
+    ExtraApprove {
+        ids: Vec<i64>,
+    },
+    ExtraReject {
+        ids: Vec<i64>,
+    },
+    /// score JP printings against TW printings into reviewable links
+    Link,
+    /// card links with the given status, as CSV on stdout
+    LinkList {
+        #[arg(long, default_value = "pending")]
+        status: ReviewStatus,
+    },
+    LinkApprove {
+        ids: Vec<i64>,
+    },
+    LinkReject {
+        ids: Vec<i64>,
+    },
     Rarity,
 }
 
 #[derive(Subcommand)]
 enum PtcgCommands {
-    Prepare,
+    Prepare {
+        #[arg(long, default_value = "tw")]
+        region: PtcgRegion,
+    },
+    /// pick up new expansions and cards without re-scraping known ones
+    Refresh {
+        #[arg(long, default_value = "tw")]
+        region: PtcgRegion,
+    },
     Run,
-    ExportCsv,
+    DownloadImages {
+        #[arg(long, default_value = "tw")]
+        region: PtcgRegion,
+    },
+    ExportCsv {
+        #[arg(long, default_value = "tw")]
+        region: PtcgRegion,
+    },
     Strategy,
+    /// propose wiki strategy rows (exp,name,strategy CSV) for expansions
+    /// found on the 52poke wiki
+    WikiDiscover {
+        /// strategy CSV already in use; expansions with a wiki source there
+        /// are skipped
+        #[arg(long)]
+        existing: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand)]
 enum WsCommands {
-    Scrape,
+    Scrape {
+        #[arg(long, default_value = "jp")]
+        region: WsRegion,
+    },
+    Sync {
+        #[arg(long, default_value = "jp")]
+        region: WsRegion,
+        /// number of newest sets to re-scrape even when already completed
+        #[arg(long, default_value_t = 3)]
+        latest: i64,
+    },
+    ScrapeDetails {
+        #[arg(long, default_value = "jp")]
+        region: WsRegion,
+    },
     DownloadImages,
     ExportCsv,
 }
@@ -82,14 +257,23 @@ enum YugiohCommands {
 
 #[derive(Subcommand)]
 enum OnePieceCommands {
-    Scrape,
-    ScrapeProducts,
+    Scrape {
+        #[arg(long, default_value = "jp")]
+        region: OnePieceRegion,
+    },
+    ScrapeProducts {
+        #[arg(long, default_value = "jp")]
+        region: OnePieceRegion,
+    },
     DownloadImages,
     ExportCsv,
-    ExportProductCsv,
+    ExportProductCsv {
+        #[arg(long, default_value = "jp")]
+        region: OnePieceRegion,
+    },
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 struct PtcgExpansionDbRow {
     exp: String,
     name: String,
@@ -106,20 +290,51 @@ async fn main() -> Result<()> {
 
     match &cli.command {
         Commands::Ptcg(commands) => match commands {
-            PtcgCommands::Prepare => {
-                let pokemon_trainer = application.ptcg();
+            PtcgCommands::Prepare { region } => {
+                let pokemon_trainer = application.ptcg(*region);
                 pokemon_trainer.prepare_ptcg_expansions().await?;
                 pokemon_trainer.update_ptcg_fetchable().await?;
                 pokemon_trainer.update_ptcg_printing().await?;
                 pokemon_trainer.update_rarity().await?;
+                pokemon_trainer.update_regulation().await?;
+                pokemon_trainer.update_energy_type().await?;
                 // pokemon_trainer.download_all_image().await?;
             }
+            PtcgCommands::Refresh { region } => {
+                let pokemon_trainer = application.ptcg(*region);
+                pokemon_trainer.refresh().await?;
+                pokemon_trainer.update_ptcg_printing().await?;
+                pokemon_trainer.update_rarity().await?;
+                pokemon_trainer.update_regulation().await?;
+                pokemon_trainer.update_energy_type().await?;
+            }
             PtcgCommands::Run => {
                 // let pokemon_trainer = application.ptcg();
             }
-            PtcgCommands::ExportCsv => {
-                let pokemon_trainer = application.ptcg();
-                let _all_cards = pokemon_trainer.export_pokemon_trainer().await?;
+            PtcgCommands::DownloadImages { region } => {
+                application.ptcg(*region).download_all_image().await?;
+            }
+            PtcgCommands::ExportCsv { region } => {
+                let wtr = std::io::stdout();
+                let pokemon_trainer = application.ptcg(*region);
+                pokemon_trainer.export_pokemon_trainer(wtr).await?;
+            }
+            PtcgCommands::WikiDiscover { existing } => {
+                let mut with_wiki = HashSet::new();
+                if let Some(path) = existing {
+                    let mut rdr = csv::Reader::from_path(path)?;
+                    for result in rdr.deserialize() {
+                        let record: PtcgExpansionDbRow = result?;
+                        let sources: Vec<Source> = serde_json::from_str(&record.strategy)?;
+                        if sources.iter().any(|s| matches!(s, Source::Wiki(_))) {
+                            with_wiki.insert(record.exp);
+                        }
+                    }
+                }
+                application
+                    .ptcg(PtcgRegion::Tw)
+                    .discover_wiki_strategies(&with_wiki, std::io::stdout())
+                    .await?;
             }
             PtcgCommands::Strategy => {
                 debug!("strategy ...");
@@ -127,9 +342,10 @@ async fn main() -> Result<()> {
                 let mut rdr = csv::Reader::from_reader(stdin);
                 for result in rdr.deserialize() {
                     let record: PtcgExpansionDbRow = result?;
-                    let ptcg = application.ptcg();
+                    debug!("running strategy for {} {}", record.exp, record.name);
+                    let ptcg = application.ptcg(PtcgRegion::Tw);
                     let sources: Vec<Source> = serde_json::from_str(&record.strategy)?;
-                    ptcg.from_expansion(sources, record).await?;
+                    ptcg.run_strategy(sources, record).await?;
                 }
             }
         },
@@ -162,60 +378,170 @@ async fn main() -> Result<()> {
                 .export_yugioh_printing_detail(wtr)
                 .await;
         }
-        Commands::Ws(WsCommands::Scrape) => {
-            let ws = application.ws();
+        Commands::Ws(WsCommands::Scrape { region }) => {
+            let ws = application.ws(*region);
             ws.scrape().await?;
         }
+        Commands::Ws(WsCommands::Sync { region, latest }) => {
+            let ws = application.ws(*region);
+            ws.sync(*latest).await?;
+        }
+        Commands::Ws(WsCommands::ScrapeDetails { region }) => {
+            let ws = application.ws(*region);
+            ws.scrape_details().await?;
+        }
         Commands::Ws(WsCommands::ExportCsv) => {
             let wtr = std::io::stdout();
-            let ws = application.ws();
+            let ws = application.ws(WsRegion::Jp);
             ws.export_csv(wtr).await?;
         }
         Commands::Ws(WsCommands::DownloadImages) => {
-            let ws = application.ws();
+            let ws = application.ws(WsRegion::Jp);
             ws.download_images("asia-tcg-marketplace-dataset", "ws_images")
                 .await?;
         }
-        Commands::OnePiece(OnePieceCommands::Scrape) => {
-            application.one_piece().scrape_one_piece().await?;
+        Commands::OnePiece(OnePieceCommands::Scrape { region }) => {
+            application.one_piece(*region).scrape_one_piece().await?;
         }
         Commands::OnePiece(OnePieceCommands::DownloadImages) => {
-            application.one_piece().download_images().await?;
+            application.one_piece_cards().download_images().await?;
         }
-        Commands::OnePiece(OnePieceCommands::ScrapeProducts) => {
-            application.one_piece().scrape_one_piece_products().await;
+        Commands::OnePiece(OnePieceCommands::ScrapeProducts { region }) => {
+            application
+                .one_piece(*region)
+                .scrape_one_piece_products()
+                .await;
         }
         Commands::OnePiece(OnePieceCommands::ExportCsv) => {
             let wtr = std::io::stdout();
-            application.one_piece().export_one_piece_csv(wtr).await?;
+            application
+                .one_piece_cards()
+                .export_one_piece_csv(wtr)
+                .await?;
         }
-        Commands::OnePiece(OnePieceCommands::ExportProductCsv) => {
+        Commands::OnePiece(OnePieceCommands::ExportProductCsv { region }) => {
             let wtr = std::io::stdout();
             application
-                .one_piece()
+                .one_piece(*region)
                 .export_one_piece_product_csv(wtr)
                 .await?;
         }
-        Commands::PtcgJp(PtcgJpCommands::Exp) => {
+        Commands::PtcgJp(PtcgJpCommands::Exp { window }) => {
+            let ptcg_jp = application.ptcg_jp();
+            ptcg_jp.update_exp(window.into()).await?;
+        }
+        Commands::PtcgJp(PtcgJpCommands::ExpMap) => {
+            let ptcg_jp = application.ptcg_jp();
+            ptcg_jp.suggest_expansion_map().await?;
+        }
+        Commands::PtcgJp(PtcgJpCommands::ExpMapList { region }) => {
+            let ptcg_jp = application.ptcg_jp();
+            ptcg_jp
+                .export_expansion_map(*region, std::io::stdout())
+                .await?;
+        }
+        Commands::PtcgJp(PtcgJpCommands::ExpMapSet {
+            jp_code,
+            tw_code,
+            en_code,
+            region,
+        }) => {
             let ptcg_jp = application.ptcg_jp();
-            ptcg_jp.update_exp().await?;
+            ptcg_jp
+                .set_expansion_mapping(*region, jp_code, tw_code, en_code.as_deref())
+                .await?;
         }
         Commands::PtcgJp(PtcgJpCommands::Card) => {
             let ptcg_jp = application.ptcg_jp();
             ptcg_jp.update_cards().await?;
         }
-        Commands::PtcgJp(PtcgJpCommands::Tc) => {
-            let ptcg_jp = application.ptcg_jp();
-            ptcg_jp.save_html().await?;
+        Commands::PtcgJp(PtcgJpCommands::Tc { html_cache, window }) => {
+            let mut ptcg_jp = application.ptcg_jp();
+            ptcg_jp.scraper.cache_dir = html_cache.clone();
+            ptcg_jp.save_tc_cards(window.into()).await?;
+        }
+        Commands::PtcgJp(PtcgJpCommands::TcBackfill) => {
+            application.ptcg_jp().backfill_tc_details().await?;
         }
         Commands::PtcgJp(PtcgJpCommands::Extra) => {
             let ptcg_jp = application.ptcg_jp();
             ptcg_jp.build_extra().await?;
         }
+        Commands::PtcgJp(PtcgJpCommands::ExtraList { status }) => {
+            let ptcg_jp = application.ptcg_jp();
+            ptcg_jp
+                .export_extra_candidates(*status, std::io::stdout())
+                .await?;
+        }
+        Commands::PtcgJp(PtcgJpCommands::ExtraApprove { ids }) => {
+            application.ptcg_jp().approve_extra(ids).await?;
+        }
+        Commands::PtcgJp(PtcgJpCommands::ExtraReject { ids }) => {
+            application.ptcg_jp().reject_extra(ids).await?;
+        }
+        Commands::PtcgJp(PtcgJpCommands::Link) => {
+            let ptcg_jp = application.ptcg_jp();
+            ptcg_jp.build_links().await?;
+        }
+        Commands::PtcgJp(PtcgJpCommands::LinkList { status }) => {
+            let ptcg_jp = application.ptcg_jp();
+            ptcg_jp
+                .export_card_links(*status, std::io::stdout())
+                .await?;
+        }
+        Commands::PtcgJp(PtcgJpCommands::LinkApprove { ids }) => {
+            application.ptcg_jp().approve_links(ids).await?;
+        }
+        Commands::PtcgJp(PtcgJpCommands::LinkReject { ids }) => {
+            application.ptcg_jp().reject_links(ids).await?;
+        }
+        Commands::PtcgEn(PtcgEnCommands::Exp { window }) => {
+            application.ptcg_en().update_exp(window.into()).await?;
+        }
+        Commands::PtcgEn(PtcgEnCommands::Card { window }) => {
+            application.ptcg_en().update_cards(window.into()).await?;
+        }
+        Commands::PtcgEn(PtcgEnCommands::ExpMap) => {
+            application.ptcg_en().suggest_expansion_map().await?;
+        }
+        Commands::PtcgEn(PtcgEnCommands::Names) => {
+            application.ptcg_en().build_name_links().await?;
+        }
+        Commands::PtcgEn(PtcgEnCommands::NameList { status }) => {
+            application
+                .ptcg_en()
+                .export_name_links(*status, std::io::stdout())
+                .await?;
+        }
+        Commands::PtcgEn(PtcgEnCommands::NameApprove { ids }) => {
+            application.ptcg_en().approve_name_links(ids).await?;
+        }
+        Commands::PtcgEn(PtcgEnCommands::NameReject { ids }) => {
+            application.ptcg_en().reject_name_links(ids).await?;
+        }
         Commands::PtcgJp(PtcgJpCommands::Rarity) => {
             let ptcg_jp = application.ptcg_jp();
             ptcg_jp.update_rarity().await?;
         }
+        Commands::Prices(PricesCommands::Bigweb) => {
+            application.prices().update_bigweb().await?;
+        }
+        Commands::Prices(PricesCommands::Report { days, top, format }) => {
+            application
+                .prices()
+                .report(*days, *top, *format, std::io::stdout())
+                .await?;
+        }
+        Commands::MatchListings => {
+            application
+                .match_listings(std::io::stdin(), std::io::stdout())
+                .await?;
+        }
+        Commands::Revisions { since } => {
+            application
+                .export_revisions(*since, std::io::stdout())
+                .await?;
+        }
         Commands::Serve(ServeCommands::Ptcg) => {
             let meilisearch_url = std::env::var("MEILISEARCH_URL")?;
             let meilisearch_api_key = std::env::var("MEILISEARCH_API_KEY")?;
@@ -224,7 +550,7 @@ async fn main() -> Result<()> {
             let state = MyState {
                 pool,
                 client,
-                ptcg: application.ptcg(),
+                ptcg: application.ptcg(PtcgRegion::Tw),
             };
             let app = Router::new()
                 .route("/", get(root))
@@ -0,0 +1,557 @@
+//! Scoring of JP (tcgcollector) printings against TW printings.
+//!
+//! The two sites share neither ids nor language, so a link is scored from the
+//! signals both sides carry: expansion code, collector number, English name,
+//! rarity, HP and the cost/damage shape of each attack. TW printings are named
+//! from EN cards the same way.
+//!
+//! Expansions are matched the same way, from their code, release date and
+//! card count; JP and EN expansions, whose codes differ, by the English card
+//! names they share. Marketplace listing titles, which are Japanese, are matched by
+//! name, rarity and art descriptors within the set their title names: to JP
+//! Pokémon printings, One Piece cards and WS cards.
+
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+
+use crate::{
+    domain::{ListingCandidate, PtcgAttack},
+    title::{ListingTitle, TitleDescriptor},
+};
+
+const EXPANSION_WEIGHT: f32 = 0.25;
+const NUMBER_WEIGHT: f32 = 0.25;
+const NAME_WEIGHT: f32 = 0.15;
+const RARITY_WEIGHT: f32 = 0.1;
+const HP_WEIGHT: f32 = 0.1;
+const ATTACKS_WEIGHT: f32 = 0.15;
+
+/// Links scoring below this are not worth reviewing.
+pub const MIN_LINK_SCORE: f32 = 0.5;
+/// Candidates kept per card, best first.
+pub const MAX_LINKS_PER_CARD: usize = 3;
+
+const EXPANSION_CODE_WEIGHT: f32 = 0.5;
+const RELEASE_DATE_WEIGHT: f32 = 0.3;
+const CARD_COUNT_WEIGHT: f32 = 0.2;
+/// TW sets come out a few weeks to a few months after their JP original.
+const RELEASE_WINDOW_DAYS: i64 = 180;
+
+/// Expansion mappings scoring below this are not suggested.
+pub const MIN_EXPANSION_SCORE: f32 = 0.5;
+
+const SHARED_NAMES_WEIGHT: f32 = 0.7;
+const EN_RELEASE_DATE_WEIGHT: f32 = 0.3;
+/// EN sets come out a few months to a year after their JP original.
+const EN_RELEASE_WINDOW_DAYS: i64 = 365;
+
+#[derive(Debug, Default)]
+pub struct CardFeatures {
+    pub expansion_code: String,
+    pub number: String,
+    /// English name; TW printings only have one once linked by hand.
+    pub name: Option<String>,
+    /// as `PtcgRarity` prints it; `Unknown` never matches
+    pub rarity: Option<String>,
+    pub hp: Option<String>,
+    pub attacks: Vec<AttackFeature>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct AttackFeature {
+    /// energy types, sorted so symbol order does not matter
+    pub cost: Vec<String>,
+    pub damage: Option<String>,
+}
+
+impl From<&PtcgAttack> for AttackFeature {
+    fn from(value: &PtcgAttack) -> Self {
+        Self::new(value.cost.clone(), value.damage.as_deref())
+    }
+}
+
+impl AttackFeature {
+    pub fn new(mut cost: Vec<String>, damage: Option<&str>) -> Self {
+        cost.sort();
+        Self {
+            cost,
+            damage: damage.map(normalize_damage).filter(|d| !d.is_empty()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct LinkScore {
+    pub score: f32,
+    /// names of the signals that matched, for reviewers
+    pub signals: Vec<&'static str>,
+}
+
+pub fn score(jp: &CardFeatures, tw: &CardFeatures) -> LinkScore {
+    let mut score = 0.0;
+    let mut signals = vec![];
+    let mut add = |matched: bool, weight: f32, signal: &'static str| {
+        if matched {
+            score += weight;
+            signals.push(signal);
+        }
+    };
+    add(
+        normalize_code(&jp.expansion_code) == normalize_code(&tw.expansion_code),
+        EXPANSION_WEIGHT,
+        "expansion",
+    );
+    add(
+        normalize_number(&jp.number) == normalize_number(&tw.number),
+        NUMBER_WEIGHT,
+        "number",
+    );
+    add(
+        matches!((&jp.name, &tw.name), (Some(a), Some(b)) if normalize_name(a) == normalize_name(b)),
+        NAME_WEIGHT,
+        "name",
+    );
+    add(
+        matches!((&jp.rarity, &tw.rarity), (Some(a), Some(b)) if a == b && a != "Unknown"),
+        RARITY_WEIGHT,
+        "rarity",
+    );
+    add(
+        matches!((&jp.hp, &tw.hp), (Some(a), Some(b)) if digits(a) == digits(b) && !digits(a).is_empty()),
+        HP_WEIGHT,
+        "hp",
+    );
+    add(
+        !jp.attacks.is_empty() && jp.attacks == tw.attacks,
+        ATTACKS_WEIGHT,
+        "attacks",
+    );
+    LinkScore { score, signals }
+}
+
+#[derive(Debug)]
+pub struct ExpansionFeatures {
+    pub code: String,
+    pub release_date: NaiveDate,
+    /// cards known so far; zero when none have been scraped yet
+    pub card_count: i64,
+}
+
+pub fn expansion_score(jp: &ExpansionFeatures, tw: &ExpansionFeatures) -> f32 {
+    let mut score = 0.0;
+    if normalize_code(&jp.code) == normalize_code(&tw.code) {
+        score += EXPANSION_CODE_WEIGHT;
+    }
+    let days = (tw.release_date - jp.release_date).num_days().abs();
+    if days < RELEASE_WINDOW_DAYS {
+        score += RELEASE_DATE_WEIGHT * (1.0 - days as f32 / RELEASE_WINDOW_DAYS as f32);
+    }
+    if jp.card_count > 0 && tw.card_count > 0 {
+        let (min, max) = if jp.card_count < tw.card_count {
+            (jp.card_count, tw.card_count)
+        } else {
+            (tw.card_count, jp.card_count)
+        };
+        score += CARD_COUNT_WEIGHT * min as f32 / max as f32;
+    }
+    score
+}
+
+#[derive(Debug)]
+pub struct ExpansionNames {
+    pub code: String,
+    pub release_date: NaiveDate,
+    /// English names of the cards known so far
+    pub names: Vec<String>,
+}
+
+/// Scores an EN expansion as the English release of a JP one, from the share
+/// of the JP card names it reprints and how soon after it came out. EN sets
+/// often combine several JP ones, so only the JP side's names must be covered.
+pub fn en_expansion_score(jp: &ExpansionNames, en: &ExpansionNames) -> f32 {
+    let jp_names: HashSet<_> = jp.names.iter().map(|n| normalize_name(n)).collect();
+    let en_names: HashSet<_> = en.names.iter().map(|n| normalize_name(n)).collect();
+    let mut score = 0.0;
+    if !jp_names.is_empty() {
+        let shared = jp_names.intersection(&en_names).count();
+        score += SHARED_NAMES_WEIGHT * shared as f32 / jp_names.len() as f32;
+    }
+    let days = (en.release_date - jp.release_date).num_days();
+    if (0..EN_RELEASE_WINDOW_DAYS).contains(&days) {
+        score += EN_RELEASE_DATE_WEIGHT * (1.0 - days as f32 / EN_RELEASE_WINDOW_DAYS as f32);
+    }
+    score
+}
+
+const LISTING_NAME_WEIGHT: f32 = 0.7;
+const LISTING_PARTIAL_NAME_WEIGHT: f32 = 0.4;
+const LISTING_RARITY_WEIGHT: f32 = 0.3;
+
+#[derive(Debug, PartialEq)]
+pub struct ListingMatch<'a> {
+    pub candidate: Option<&'a ListingCandidate>,
+    pub confidence: f32,
+    /// why the match is not certain, for whoever reviews it
+    pub reasons: Vec<String>,
+}
+
+/// Picks the printing in `candidates`, all from the title's set, that the
+/// title most likely lists.
+pub fn match_listing<'a>(
+    title: &ListingTitle,
+    candidates: &'a [ListingCandidate],
+) -> ListingMatch<'a> {
+    let mut reasons = vec![];
+    if candidates.is_empty() {
+        reasons.push(format!("no card in set {}", title.set_ref));
+    }
+    let mut tags = vec![];
+    // art and trainer descriptors, which catalogues put in the card name
+    let mut variants = vec![];
+    for descriptor in &title.descriptors {
+        match descriptor {
+            TitleDescriptor::Tag(tag) => tags.push(tag.to_lowercase()),
+            TitleDescriptor::AlterArt(text) => variants.push(("alter art", text)),
+            TitleDescriptor::TrainerName(text) => variants.push(("trainer", text)),
+            TitleDescriptor::SpecialArt(text) => variants.push(("special art", text)),
+        }
+    }
+    let variant_names: Vec<_> = variants
+        .iter()
+        .map(|(_, text)| normalize_name(text))
+        .filter(|v| !v.is_empty())
+        .collect();
+    let name = normalize_name(&title.card_name);
+    let full_names: Vec<_> = std::iter::once(name.clone())
+        .chain(variant_names.iter().map(|v| format!("{name}{v}")))
+        .collect();
+    // (candidate, full name match, rarity match, variant match)
+    let scored: Vec<_> = candidates
+        .iter()
+        .filter_map(|candidate| {
+            let names = std::iter::once(&candidate.name).chain(candidate.alt_name.iter());
+            let names: Vec<_> = names
+                .map(|n| normalize_name(n))
+                .filter(|n| !n.is_empty())
+                .collect();
+            let full_name = if names.iter().any(|n| full_names.contains(n)) {
+                true
+            } else if !name.is_empty()
+                && names.iter().any(|n| n.contains(&name) || name.contains(n))
+            {
+                false
+            } else {
+                return None;
+            };
+            let rarity = candidate.rarity.as_deref().map(str::to_lowercase);
+            let rarity = rarity.is_some_and(|r| tags.contains(&r));
+            let variant = names
+                .iter()
+                .any(|n| variant_names.iter().any(|v| n.contains(v)));
+            Some((candidate, full_name, rarity, variant))
+        })
+        .collect();
+    let score = |full_name: bool, rarity: bool| {
+        let name = if full_name {
+            LISTING_NAME_WEIGHT
+        } else {
+            LISTING_PARTIAL_NAME_WEIGHT
+        };
+        name + if rarity { LISTING_RARITY_WEIGHT } else { 0.0 }
+    };
+    // the variant only breaks ties, it is missing from most catalogues
+    let best = scored
+        .iter()
+        .max_by(|a, b| {
+            score(a.1, a.2)
+                .total_cmp(&score(b.1, b.2))
+                .then(a.3.cmp(&b.3))
+        })
+        .map(|&(_, full_name, rarity, variant)| (full_name, rarity, variant));
+    let Some((full_name, rarity, variant)) = best else {
+        if !candidates.is_empty() {
+            reasons.push(format!("no card named {}", title.card_name));
+        }
+        return ListingMatch {
+            candidate: None,
+            confidence: 0.0,
+            reasons,
+        };
+    };
+    let tied: Vec<_> = scored
+        .iter()
+        .filter(|(_, f, r, v)| *f == full_name && *r == rarity && *v == variant)
+        .map(|(c, _, _, _)| *c)
+        .collect();
+    if !full_name {
+        reasons.push("name only partly matches".to_string());
+    }
+    if !rarity {
+        reasons.push(if tags.is_empty() {
+            "no rarity in title".to_string()
+        } else {
+            format!("rarity {} not found", tags.join("/"))
+        });
+    }
+    if !variant {
+        for (kind, text) in &variants {
+            reasons.push(format!("{kind} {text} not in card name"));
+        }
+    }
+    if tied.len() > 1 {
+        reasons.push(format!("{} cards match equally", tied.len()));
+    }
+    ListingMatch {
+        candidate: tied.first().copied(),
+        confidence: score(full_name, rarity) / tied.len() as f32,
+        reasons,
+    }
+}
+
+/// `SV5K` and `sv5k` are the same set on both sites.
+pub fn normalize_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// `001/071` -> `1`, so zero padding and set size do not matter.
+pub fn normalize_number(number: &str) -> String {
+    let number = number.split('/').next().unwrap_or_default().trim();
+    let trimmed = number.trim_start_matches('0');
+    if trimmed.is_empty() && !number.is_empty() {
+        "0".to_string()
+    } else {
+        trimmed.to_ascii_lowercase()
+    }
+}
+
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn normalize_damage(damage: &str) -> String {
+    damage
+        .chars()
+        .filter_map(|c| match c {
+            '＋' => Some('+'),
+            '×' | 'x' | 'X' => Some('x'),
+            c if c.is_ascii_digit() || c == '+' || c == '-' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+fn digits(s: &str) -> String {
+    s.chars().filter(char::is_ascii_digit).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attack(cost: &[&str], damage: Option<&str>) -> AttackFeature {
+        AttackFeature::new(cost.iter().map(|c| c.to_string()).collect(), damage)
+    }
+
+    #[test]
+    fn normalizes_codes_numbers_and_names() {
+        assert_eq!(normalize_code("SV5K"), "sv5k");
+        assert_eq!(normalize_code("s-p"), "sp");
+        assert_eq!(normalize_number("007/071"), "7");
+        assert_eq!(normalize_number("000"), "0");
+        assert_eq!(normalize_number("SV-P 012"), "sv-p 012");
+        assert_eq!(normalize_name("Pikachu ex"), normalize_name("PIKACHU-EX"));
+    }
+
+    #[test]
+    fn attack_cost_order_and_damage_format_are_ignored() {
+        assert_eq!(
+            attack(&["Fire", "Colorless"], Some("30＋")),
+            attack(&["Colorless", "Fire"], Some("30+"))
+        );
+        assert_eq!(attack(&[], Some("")), attack(&[], None));
+    }
+
+    #[test]
+    fn scores_every_signal() {
+        let jp = CardFeatures {
+            expansion_code: "sv5k".to_string(),
+            number: "012/071".to_string(),
+            name: Some("Pikachu".to_string()),
+            rarity: Some("C".to_string()),
+            hp: Some("HP 60".to_string()),
+            attacks: vec![attack(&["Lightning"], Some("20"))],
+        };
+        let tw = CardFeatures {
+            expansion_code: "SV5K".to_string(),
+            number: "012/071".to_string(),
+            name: None,
+            rarity: Some("C".to_string()),
+            hp: Some("60".to_string()),
+            attacks: vec![attack(&["Lightning"], Some("20"))],
+        };
+        let link = score(&jp, &tw);
+        assert_eq!(
+            link.signals,
+            vec!["expansion", "number", "rarity", "hp", "attacks"]
+        );
+        assert!((link.score - 0.85).abs() < 1e-6);
+        let unknown = CardFeatures {
+            rarity: Some("Unknown".to_string()),
+            ..Default::default()
+        };
+        assert!(!score(&unknown, &unknown).signals.contains(&"rarity"));
+
+        let unrelated = CardFeatures {
+            expansion_code: "SV4a".to_string(),
+            number: "100/190".to_string(),
+            ..Default::default()
+        };
+        assert!(score(&jp, &unrelated).score < MIN_LINK_SCORE);
+    }
+
+    /// A JP printing as the catalogue gives it: the bigweb name, when bigweb
+    /// sells the card, and the tcgcollector English name.
+    fn candidate(id: &str, name: &str, rarity: &str) -> ListingCandidate {
+        let english = match name {
+            "ライチュウ" => "Raichu",
+            "ミュウ" => "Mew",
+            _ => "Pikachu",
+        };
+        ListingCandidate {
+            game: "ptcg".to_string(),
+            id: format!(
+                "https://www.tcgcollector.com/cards/{id}/{}",
+                english.to_lowercase()
+            ),
+            name: name.to_string(),
+            alt_name: Some(english.to_string()),
+            rarity: Some(rarity.to_string()),
+        }
+    }
+    fn id(found: &ListingMatch) -> Option<String> {
+        found
+            .candidate
+            .map(|c| c.id.split('/').nth(4).unwrap().to_string())
+    }
+
+    #[test]
+    fn listing_rarity_breaks_name_ties() {
+        let candidates = vec![
+            candidate("1", "ピカチュウ", "C"),
+            candidate("2", "ピカチュウ", "SAR"),
+            candidate("3", "ライチュウ", "SAR"),
+        ];
+        let title: ListingTitle = "[【SV5K】]ピカチュウ【SAR】".parse().unwrap();
+        let found = match_listing(&title, &candidates);
+        assert_eq!(id(&found).as_deref(), Some("2"));
+        assert!((found.confidence - 1.0).abs() < 1e-6);
+        assert!(found.reasons.is_empty(), "{:?}", found.reasons);
+
+        let title: ListingTitle = "[【SV5K】]ピカチュウ".parse().unwrap();
+        let found = match_listing(&title, &candidates);
+        assert!((found.confidence - 0.35).abs() < 1e-6);
+        assert_eq!(
+            found.reasons,
+            vec!["no rarity in title", "2 cards match equally"]
+        );
+    }
+
+    #[test]
+    fn listing_descriptor_breaks_name_ties() {
+        let candidates = vec![
+            candidate("1", "ピカチュウ", "C"),
+            candidate("2", "ピカチュウ(マスターボールミラー)", "C"),
+        ];
+        let title: ListingTitle = "[【SV2a】]ピカチュウ【C】(マスターボールミラー)"
+            .parse()
+            .unwrap();
+        let found = match_listing(&title, &candidates);
+        assert_eq!(id(&found).as_deref(), Some("2"));
+        assert!(found.reasons.is_empty(), "{:?}", found.reasons);
+
+        let title: ListingTitle = "[【SV2a】]ピカチュウ【C】（ナンジャモ）".parse().unwrap();
+        let found = match_listing(&title, &candidates);
+        assert_eq!(id(&found).as_deref(), Some("1"));
+        assert_eq!(found.reasons, vec!["trainer ナンジャモ not in card name"]);
+    }
+
+    #[test]
+    fn listing_without_japanese_name_cannot_match() {
+        // cards bigweb does not sell only have their English name
+        let candidates = [ListingCandidate {
+            name: "Pikachu".to_string(),
+            alt_name: None,
+            ..candidate("1", "ピカチュウ", "C")
+        }];
+        let title: ListingTitle = "[【SV5K】]ピカチュウ【C】".parse().unwrap();
+        let found = match_listing(&title, &candidates);
+        assert_eq!(found.candidate, None);
+        assert_eq!(found.reasons, vec!["no card named ピカチュウ"]);
+    }
+
+    #[test]
+    fn listing_skips_empty_candidate_names() {
+        let candidates = [ListingCandidate {
+            alt_name: None,
+            ..candidate("1", "・", "C")
+        }];
+        let title: ListingTitle = "[【SV5K】]ピカチュウ【C】".parse().unwrap();
+        let found = match_listing(&title, &candidates);
+        assert_eq!(found.candidate, None);
+    }
+
+    #[test]
+    fn listing_without_candidates_explains_why() {
+        let title: ListingTitle = "[【SV5K】]ミュウ【AR】".parse().unwrap();
+        let found = match_listing(&title, &[]);
+        assert_eq!(found.candidate, None);
+        assert_eq!(found.reasons, vec!["no card in set SV5K"]);
+        let candidates = [candidate("1", "ピカチュウ", "C")];
+        let found = match_listing(&title, &candidates);
+        assert_eq!(found.reasons, vec!["no card named ミュウ"]);
+    }
+
+    #[test]
+    fn expansion_code_outweighs_date_and_count() {
+        let exp = |code: &str, date: &str, card_count| ExpansionFeatures {
+            code: code.to_string(),
+            release_date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            card_count,
+        };
+        let jp = exp("sv5k", "2024-01-26", 71);
+        let same = expansion_score(&jp, &exp("SV5K", "2024-03-08", 71));
+        assert!(same > 0.9, "{same}");
+        let neighbour = expansion_score(&jp, &exp("SV5M", "2024-03-08", 71));
+        assert!(neighbour < MIN_EXPANSION_SCORE, "{neighbour}");
+        let unscraped = expansion_score(&jp, &exp("SV5K", "2024-03-08", 0));
+        assert!(unscraped >= MIN_EXPANSION_SCORE, "{unscraped}");
+    }
+
+    #[test]
+    fn en_expansion_is_the_later_one_reprinting_the_jp_names() {
+        let exp = |code: &str, date: &str, names: &[&str]| ExpansionNames {
+            code: code.to_string(),
+            release_date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            names: names.iter().map(|n| n.to_string()).collect(),
+        };
+        let jp = exp("SV5K", "2024-01-26", &["Iron Crown ex", "Pikachu", "Mew"]);
+        let combined = exp(
+            "TEF",
+            "2024-03-22",
+            &["Iron Crown ex", "Pikachu", "Mew", "Walking Wake ex"],
+        );
+        let combined = en_expansion_score(&jp, &combined);
+        assert!(combined >= MIN_EXPANSION_SCORE, "{combined}");
+        let earlier = en_expansion_score(&jp, &exp("PAR", "2023-11-03", &["Pikachu"]));
+        assert!(earlier < MIN_EXPANSION_SCORE, "{earlier}");
+        let unrelated = en_expansion_score(&jp, &exp("TWM", "2024-05-24", &["Ogerpon ex"]));
+        assert!(unrelated < MIN_EXPANSION_SCORE, "{unrelated}");
+    }
+}
@@ -0,0 +1,193 @@
+//! Moving averages, movers and anomalies over the `card_prices` history.
+//!
+//! Prices are reduced to one per card and day, the last one seen that day.
+//! A price is an anomaly when it sits [`ANOMALY_SIGMA`] standard deviations
+//! or more from the card's previous 30 days, or moves at all after a flat
+//! stretch.
+
+use std::collections::BTreeMap;
+
+use chrono::{Days, NaiveDate};
+
+use crate::domain::{PricePoint, PriceReportRow};
+
+pub const ANOMALY_SIGMA: f64 = 3.0;
+/// Fewer earlier days than this say nothing about a card's spread.
+const MIN_HISTORY: usize = 5;
+const SHORT_WINDOW: Days = Days::new(7);
+const LONG_WINDOW: Days = Days::new(30);
+
+/// The `top` movers of each game and set, by absolute change, followed by
+/// the set's other anomalies. `points` must be sorted by card, then date.
+pub fn report(points: &[PricePoint], top: usize) -> Vec<PriceReportRow> {
+    let mut sets: BTreeMap<(String, String), Vec<PriceReportRow>> = BTreeMap::new();
+    for card in points.chunk_by(|a, b| a.source == b.source && a.card_key == b.card_key) {
+        if let Some(row) = card_stats(card) {
+            sets.entry((row.game.clone(), row.set_code.clone()))
+                .or_default()
+                .push(row);
+        }
+    }
+    let mut rows = vec![];
+    for (_, mut cards) in sets {
+        let moved = |r: &PriceReportRow| r.change_pct.map(f64::abs);
+        cards.sort_by(|a, b| moved(b).partial_cmp(&moved(a)).unwrap());
+        let rest = cards.split_off(top.min(cards.len()));
+        rows.extend(cards);
+        rows.extend(rest.into_iter().filter(|r| r.anomaly));
+    }
+    rows
+}
+
+/// Stats of one card as of its latest price; `points` are that card's, by date.
+fn card_stats(points: &[PricePoint]) -> Option<PriceReportRow> {
+    let last = points.last()?;
+    let daily = daily_prices(points);
+    let (today, price) = *daily.last()?;
+    let since = |window| today.checked_sub_days(window).unwrap_or(NaiveDate::MIN);
+
+    let ma7 = mean(daily.iter().filter(|(d, _)| *d > since(SHORT_WINDOW)));
+    let ma30 = mean(daily.iter().filter(|(d, _)| *d > since(LONG_WINDOW)));
+    let change_pct = daily
+        .iter()
+        .rev()
+        .find(|(d, _)| *d <= since(SHORT_WINDOW))
+        .filter(|(_, base)| *base != 0)
+        .map(|(_, base)| round((price - base) as f64 / *base as f64 * 100.0));
+
+    let history: Vec<f64> = daily
+        .iter()
+        .filter(|(d, _)| *d >= since(LONG_WINDOW) && *d < today)
+        .map(|(_, p)| *p as f64)
+        .collect();
+    let (z_score, anomaly) = if history.len() < MIN_HISTORY {
+        (None, false)
+    } else {
+        let mean = history.iter().sum::<f64>() / history.len() as f64;
+        let variance =
+            history.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / history.len() as f64;
+        let sd = variance.sqrt();
+        if sd == 0.0 {
+            (None, price as f64 != mean)
+        } else {
+            let z = (price as f64 - mean) / sd;
+            (Some(round(z)), z.abs() >= ANOMALY_SIGMA)
+        }
+    };
+
+    Some(PriceReportRow {
+        game: last.game.clone(),
+        set_code: last.set_code.clone(),
+        source: last.source.clone(),
+        card_key: last.card_key.clone(),
+        name: last.name.clone(),
+        observed_on: today.to_string(),
+        price,
+        ma7: round(ma7),
+        ma30: round(ma30),
+        change_pct,
+        z_score,
+        anomaly,
+    })
+}
+
+/// The last price of each day.
+fn daily_prices(points: &[PricePoint]) -> Vec<(NaiveDate, i64)> {
+    let mut daily: Vec<(NaiveDate, i64)> = vec![];
+    for point in points {
+        match daily.last_mut() {
+            Some(last) if last.0 == point.observed_on => last.1 = point.price,
+            _ => daily.push((point.observed_on, point.price)),
+        }
+    }
+    daily
+}
+
+fn mean<'a>(prices: impl Iterator<Item = &'a (NaiveDate, i64)>) -> f64 {
+    let (sum, count) = prices.fold((0, 0), |(sum, count), (_, p)| (sum + p, count + 1));
+    sum as f64 / count.max(1) as f64
+}
+
+fn round(x: f64) -> f64 {
+    (x * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(card_key: &str, set_code: &str, prices: &[(u32, i64)]) -> Vec<PricePoint> {
+        prices
+            .iter()
+            .map(|(day, price)| PricePoint {
+                game: "ptcg".to_string(),
+                set_code: set_code.to_string(),
+                source: "bigweb".to_string(),
+                card_key: card_key.to_string(),
+                name: format!("card {}", card_key),
+                price: *price,
+                observed_on: NaiveDate::from_ymd_opt(2024, 8, *day).unwrap(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn moving_averages_and_change() {
+        let card = points(
+            "1",
+            "SV5K",
+            &[(1, 100), (2, 100), (8, 120), (9, 200), (9, 140)],
+        );
+        let row = card_stats(&card).unwrap();
+        assert_eq!(row.observed_on, "2024-08-09");
+        assert_eq!(row.price, 140);
+        assert_eq!(row.ma7, 130.0);
+        assert_eq!(row.ma30, 115.0);
+        assert_eq!(row.change_pct, Some(40.0));
+        assert_eq!(row.z_score, None);
+        assert!(!row.anomaly);
+    }
+
+    #[test]
+    fn flag_three_sigma_jump() {
+        let steady = [(1, 100), (2, 102), (3, 98), (4, 101), (5, 99), (6, 100)];
+        let mut prices = steady.to_vec();
+        prices.push((7, 160));
+        let row = card_stats(&points("1", "SV5K", &prices)).unwrap();
+        assert!(row.anomaly);
+        assert!(row.z_score.unwrap() >= ANOMALY_SIGMA);
+
+        let mut prices = steady.to_vec();
+        prices.push((7, 103));
+        let row = card_stats(&points("1", "SV5K", &prices)).unwrap();
+        assert!(!row.anomaly);
+
+        let flat = points(
+            "1",
+            "SV5K",
+            &[(1, 50), (2, 50), (3, 50), (4, 50), (5, 50), (6, 60)],
+        );
+        let row = card_stats(&flat).unwrap();
+        assert_eq!(row.z_score, None);
+        assert!(row.anomaly);
+    }
+
+    #[test]
+    fn top_movers_per_set_keep_anomalies() {
+        let mut all = points("1", "SV5K", &[(1, 100), (8, 110)]);
+        all.extend(points("2", "SV5K", &[(1, 100), (8, 300)]));
+        all.extend(points(
+            "3",
+            "SV5K",
+            &[(1, 10), (2, 10), (3, 10), (4, 10), (5, 10), (6, 11)],
+        ));
+        all.extend(points("4", "SV6", &[(1, 100), (8, 50)]));
+        let rows = report(&all, 1);
+        let keys: Vec<(&str, &str)> = rows
+            .iter()
+            .map(|r| (r.set_code.as_str(), r.card_key.as_str()))
+            .collect();
+        assert_eq!(keys, vec![("SV5K", "2"), ("SV5K", "3"), ("SV6", "4")]);
+        assert_eq!(rows[2].change_pct, Some(-50.0));
+    }
+}
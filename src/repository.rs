@@ -1,16 +1,26 @@
-use crate::domain::{LastFetchedAt, PokemonCard, PtcgRarity};
+use crate::domain::{
+    CardRevision, Language, ListingCandidate, PokemonCard, PricePoint, PtcgAttack, PtcgCardLink,
+    PtcgEnNameLink, PtcgExpansionMapping, PtcgExtraCandidate, PtcgExtraSource, PtcgRarity,
+    ReviewStatus,
+};
+use crate::matching::{AttackFeature, CardFeatures, ExpansionFeatures, ExpansionNames, LinkScore};
+use crate::scraper::bigweb::{BigwebCardset, BigwebProduct};
 use crate::scraper::one_piece::{OnePieceCard, OnePieceCardRarity, OnePieceCardType};
 use crate::scraper::pokemon_wiki::PokemonWikiCard;
-use crate::scraper::ptcg::{PtcgExpansion, ThePTCGCard};
+use crate::scraper::ptcg::{energy_type, PtcgExpansion, PtcgRegion, ThePTCGCard};
 use crate::scraper::tcg_collector::{
-    PtcgJpCard, PtcgJpExpansion, TcgCollectorCardDetail, TcgCollectorCardRarity,
+    PtcgJpCard, ReleaseWindow, TcgCollectorCard, TcgCollectorCardRarity, TcgCollectorExpansion,
 };
-use crate::scraper::ws::WsCard;
+use crate::scraper::ws::{WsCard, WsCardDetail, WsExpansion, WsRegion};
 use crate::scraper::yugioh::YugiohPrinting;
+use chrono::NaiveDate;
 use futures::stream::BoxStream;
 use futures::{StreamExt, TryStreamExt};
 use sqlx::postgres::PgPoolOptions;
-use sqlx::{Pool, Postgres, Transaction};
+use sqlx::{PgConnection, Pool, Postgres, Transaction};
+use std::collections::HashMap;
+use time::OffsetDateTime;
+use tracing::warn;
 
 #[derive(Clone)]
 pub struct Repository {
@@ -23,10 +33,14 @@ impl Repository {
         Ok(Self { pool })
     }
 
-    pub fn find_ptcg_expansion(&self) -> BoxStream<Result<PtcgExpansion, RepositoryError>> {
+    pub fn find_ptcg_expansion(
+        &self,
+        region: PtcgRegion,
+    ) -> BoxStream<'_, Result<PtcgExpansion, RepositoryError>> {
         sqlx::query_as!(
             PtcgExpansion,
-            "SELECT code, series, name, release_date FROM pokemon_trainer_expansion"
+            "SELECT code, series, name, release_date FROM pokemon_trainer_expansion WHERE region = $1",
+            region.to_string()
         )
         .fetch(&self.pool)
         .map_err(RepositoryError::from)
@@ -37,68 +51,160 @@ impl Repository {
         &self,
         cards: Vec<PokemonWikiCard>,
     ) -> Result<(), RepositoryError> {
-        let unzipped = cards
-            .into_iter()
-            .fold((vec![], vec![], vec![], vec![]), |mut acc, card| {
-                acc.0.push(card.number);
-                acc.1.push(card.name);
-                acc.2.push(card.exp_code);
-                acc.3.push(card.rarity);
-                acc
-            });
-        sqlx::query!(
-            "
-    INSERT INTO pokewiki(number, name, exp_code, rarity)
-    SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[], $4::ptcg_rarity_enum[])
-    ON CONFLICT (number, name, rarity, exp_code)
-    DO NOTHING
-    ",
-            &unzipped.0,
-            &unzipped.1,
-            &unzipped.2,
-            &unzipped.3 as &Vec<PtcgRarity>,
-        )
-        .execute(&self.pool)
-        .await?;
+        for card in cards {
+            sqlx::query!(
+                "
+                INSERT INTO pokewiki(
+                    number, name, exp_code, rarity, card_type, link, image_src, name_chinese,
+                    text_chinese)
+                VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (number, name, rarity, exp_code)
+                DO UPDATE SET card_type = $5, link = $6, image_src = $7,
+                    name_chinese = COALESCE($8, pokewiki.name_chinese),
+                    text_chinese = COALESCE($9, pokewiki.text_chinese)
+                ",
+                card.number,
+                card.name,
+                card.exp_code,
+                card.rarity as PtcgRarity,
+                card.card_type,
+                card.link,
+                card.image_src,
+                card.name_chinese,
+                card.text_chinese,
+            )
+            .execute(&self.pool)
+            .await?;
+        }
         Ok(())
     }
-    pub fn get_tc_details(&self) -> BoxStream<Result<TcgCollectorCardDetail, RepositoryError>> {
-        sqlx::query_as!(
-            TcgCollectorCardDetail,
-            r#"SELECT name, number, exp_code, html, url, rarity AS "rarity: _" from tcg_collector"#
+    pub async fn get_ptcg_jp_cards(&self) -> Result<Vec<PtcgJpCard>, RepositoryError> {
+        let cards = sqlx::query!(
+            r#"
+            SELECT name, number, exp_code, url, description, hp, rarity AS "rarity: PtcgRarity"
+            FROM tcg_collector
+            "#
         )
-        .fetch(&self.pool)
-        .map_err(RepositoryError::from)
-        .boxed()
+        .fetch_all(&self.pool)
+        .await?;
+        let attacks = sqlx::query_as!(
+            TcgCollectorAttack,
+            "
+            SELECT card_url, name, cost, damage, effect FROM tcg_collector_attack
+            ORDER BY card_url, position
+            "
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let mut attacks_by_url: HashMap<String, Vec<PtcgAttack>> = HashMap::new();
+        for a in attacks {
+            attacks_by_url
+                .entry(a.card_url)
+                .or_default()
+                .push(PtcgAttack {
+                    name: a.name,
+                    cost: a.cost,
+                    damage: a.damage,
+                    effect: a.effect,
+                });
+        }
+        let cards = cards
+            .into_iter()
+            .map(|c| {
+                let attacks = attacks_by_url.remove(&c.url).unwrap_or_default();
+                PtcgJpCard {
+                    name: c.name,
+                    number: c.number,
+                    exp_code: c.exp_code,
+                    desc: c.description,
+                    hp: c.hp,
+                    skill1_name_en: attacks.first().map(|a| a.name.clone()),
+                    skill1_damage: attacks.first().and_then(|a| a.damage.clone()),
+                    attacks,
+                    url: c.url,
+                    rarity: c.rarity,
+                }
+            })
+            .collect();
+        Ok(cards)
     }
-    pub async fn get_ptcg_jp_expansions_links(&self) -> Result<Vec<String>, RepositoryError> {
+    pub async fn get_ptcg_jp_expansions_links(
+        &self,
+        window: ReleaseWindow,
+    ) -> Result<Vec<String>, RepositoryError> {
         let links = sqlx::query!(
             "
             SELECT exp_link
-            FROM pokemon_trainer_expansion pt
-            LEFT JOIN ptcg_jp_expansions jp on pt.code = jp.code"
+            FROM ptcg_expansion_map m
+            JOIN ptcg_jp_expansions jp ON jp.code = m.jp_code
+            WHERE m.region = 'tw'
+                AND ($1::DATE IS NULL OR jp.release_date >= $1)
+                AND ($2::DATE IS NULL OR jp.release_date <= $2)",
+            window.since,
+            window.until
         )
         .fetch_all(&self.pool)
         .await?;
         Ok(links.into_iter().filter_map(|r| r.exp_link).collect())
     }
-    pub async fn save_tcg_collector(
-        &self,
-        details: Vec<TcgCollectorCardDetail>,
-    ) -> Result<(), RepositoryError> {
-        for detail in details {
+    pub async fn save_tcg_collector(&self, cards: Vec<PtcgJpCard>) -> Result<(), RepositoryError> {
+        for card in cards {
+            let mut tx = self.pool.begin().await?;
             sqlx::query!(
-                "INSERT INTO tcg_collector(name, number, exp_code, html, url) VALUES ($1, $2, $3, $4, $5)",
-                detail.name,
-                detail.number,
-                detail.exp_code,
-                detail.html,
-                detail.url
-            ).execute(&self.pool).await?;
+                "
+                INSERT INTO tcg_collector(name, number, exp_code, url, description, hp, rarity)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT(url)
+                DO UPDATE SET name = $1, number = $2, exp_code = $3, description = $5, hp = $6,
+                    rarity = $7, html = NULL
+                ",
+                card.name,
+                card.number,
+                card.exp_code,
+                card.url,
+                card.desc,
+                card.hp,
+                card.rarity as Option<PtcgRarity>,
+            )
+            .execute(&mut *tx)
+            .await?;
+            write_tcg_collector_attacks(&mut tx, &card.url, &card.attacks).await?;
+            tx.commit().await?;
         }
         Ok(())
     }
 
+    /// Detail pages stored before details were parsed on ingest, by url.
+    pub async fn get_unparsed_tcg_collector(
+        &self,
+    ) -> Result<Vec<(String, String)>, RepositoryError> {
+        let pages = sqlx::query!(
+            r#"SELECT url, html AS "html!" FROM tcg_collector WHERE html IS NOT NULL AND html <> ''"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(pages.into_iter().map(|p| (p.url, p.html)).collect())
+    }
+    /// Stores what was parsed from a stored detail page and drops the page.
+    pub async fn save_tcg_collector_detail(
+        &self,
+        url: &str,
+        detail: &PtcgJpCard,
+    ) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            "UPDATE tcg_collector SET description = $2, hp = $3, html = NULL WHERE url = $1",
+            url,
+            detail.desc,
+            detail.hp
+        )
+        .execute(&mut *tx)
+        .await?;
+        write_tcg_collector_attacks(&mut tx, url, &detail.attacks).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
     pub async fn update_tc_rarity(
         &self,
         card_rarities: Vec<TcgCollectorCardRarity>,
@@ -116,7 +222,7 @@ impl Repository {
     }
     pub async fn save_ptcg_jp_expansions(
         &self,
-        exps: Vec<PtcgJpExpansion>,
+        exps: Vec<TcgCollectorExpansion>,
     ) -> Result<(), RepositoryError> {
         for exp in exps {
             match sqlx::query!(
@@ -137,52 +243,681 @@ impl Repository {
         }
         Ok(())
     }
-    pub async fn ptcg_tw_is_exists(
+    pub async fn save_ptcg_en_expansions(
         &self,
-        detail: &TcgCollectorCardDetail,
-    ) -> Result<bool, RepositoryError> {
-        let r = sqlx::query!(
-            "SELECT * FROM pokemon_trainer_printing WHERE number = $1 AND expansion_code = $2",
-            detail.number,
-            detail.exp_code
+        exps: Vec<TcgCollectorExpansion>,
+    ) -> Result<(), RepositoryError> {
+        for exp in exps {
+            sqlx::query!(
+                "
+                INSERT INTO ptcg_en_expansions(code, name_en, exp_link, symbol_src, logo_src, release_date)
+                VALUES($1, $2, $3, $4, $5, $6)
+                ON CONFLICT(code)
+                DO UPDATE SET name_en = $2, exp_link = $3, symbol_src = $4, logo_src = $5,
+                    release_date = $6
+                ",
+                exp.code,
+                exp.name,
+                exp.link,
+                exp.symbol_src,
+                exp.logo_src,
+                exp.release_date
+            )
+            .execute(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+    pub async fn get_ptcg_en_expansions_links(
+        &self,
+        window: ReleaseWindow,
+    ) -> Result<Vec<String>, RepositoryError> {
+        let links = sqlx::query!(
+            "
+            SELECT exp_link FROM ptcg_en_expansions
+            WHERE ($1::DATE IS NULL OR release_date >= $1)
+                AND ($2::DATE IS NULL OR release_date <= $2)
+            ",
+            window.since,
+            window.until
         )
-        .fetch_one(&self.pool)
-        .await
-        .is_ok();
-        Ok(r)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(links.into_iter().filter_map(|r| r.exp_link).collect())
     }
-    pub async fn save_extra(&self, card: PtcgJpCard) -> Result<(), RepositoryError> {
-        let results = sqlx::query!(
+    pub async fn save_ptcg_en_cards(
+        &self,
+        cards: Vec<TcgCollectorCard>,
+    ) -> Result<(), RepositoryError> {
+        for card in cards {
+            sqlx::query!(
+                "
+                INSERT INTO ptcg_en_cards(url, name, number, exp_code, rarity, image_src)
+                VALUES($1, $2, $3, $4, $5, $6)
+                ON CONFLICT(url)
+                DO UPDATE SET name = $2, number = $3, exp_code = $4, rarity = $5, image_src = $6,
+                    last_fetched_at = NOW()
+                ",
+                card.url,
+                card.name,
+                card.number,
+                card.exp_code,
+                card.rarity as PtcgRarity,
+                card.image_src
+            )
+            .execute(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+    /// TW printings without an English name, with the EN expansion mapped to
+    /// theirs. Several JP expansions mapping to one TW expansion yield one row.
+    /// Printings linked to a JP printing carry its English name.
+    pub async fn get_en_name_printings(
+        &self,
+    ) -> Result<Vec<(String, CardFeatures)>, RepositoryError> {
+        let printings = sqlx::query!(
+            r#"
+            SELECT DISTINCT p.code, m.en_code AS "en_code!", p.number, p.rarity, jp.name AS "jp_name?"
+            FROM pokemon_trainer_printing p
+            JOIN ptcg_expansion_map m ON m.region = p.region AND m.tw_code = p.expansion_code
+            LEFT JOIN LATERAL (
+                SELECT tc.name FROM ptcg_card_links l
+                JOIN tcg_collector tc ON tc.url = l.tc_url
+                WHERE l.status = 'approved' AND l.region = p.region AND l.printing_code = p.code
+                LIMIT 1
+            ) jp ON true
+            WHERE p.region = 'tw' AND p.code NOT LIKE 'd|%' AND p.name_en IS NULL
+                AND m.en_code IS NOT NULL
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(printings
+            .into_iter()
+            .map(|p| {
+                let features = CardFeatures {
+                    // compared against EN codes, so use the mapped one
+                    expansion_code: p.en_code,
+                    number: p.number,
+                    name: p.jp_name,
+                    rarity: p.rarity,
+                    ..Default::default()
+                };
+                (p.code, features)
+            })
+            .collect())
+    }
+    /// EN cards by url; the EN listing carries no HP or attacks.
+    pub async fn get_ptcg_en_card_features(
+        &self,
+        en_code: &str,
+    ) -> Result<Vec<(String, CardFeatures)>, RepositoryError> {
+        let cards = sqlx::query!(
+            "SELECT url, name, number, exp_code, rarity::TEXT FROM ptcg_en_cards WHERE exp_code = $1",
+            en_code
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(cards
+            .into_iter()
+            .map(|c| {
+                let features = CardFeatures {
+                    expansion_code: c.exp_code,
+                    number: c.number,
+                    name: Some(c.name),
+                    rarity: c.rarity,
+                    ..Default::default()
+                };
+                (c.url, features)
+            })
+            .collect())
+    }
+    pub async fn upsert_en_name_link(
+        &self,
+        en_url: &str,
+        printing_code: &str,
+        link: &LinkScore,
+    ) -> Result<(), RepositoryError> {
+        let signals: Vec<String> = link.signals.iter().map(|s| s.to_string()).collect();
+        sqlx::query!(
+            "
+            INSERT INTO ptcg_en_name_links(en_url, printing_code, score, signals)
+            VALUES($1, $2, $3, $4)
+            ON CONFLICT(en_url, region, printing_code)
+            DO UPDATE SET score = $3, signals = $4
+            WHERE ptcg_en_name_links.status = 'pending'
+            ",
+            en_url,
+            printing_code,
+            link.score,
+            &signals
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+    pub async fn get_en_name_links(
+        &self,
+        status: ReviewStatus,
+    ) -> Result<Vec<PtcgEnNameLink>, RepositoryError> {
+        let links = sqlx::query_as!(
+            PtcgEnNameLink,
             r#"
-            -- INSERT INTO pokemon_trainer_printing(code, name, kind, number, rarity, expansion_code, name_en, skill1_name_en, skill1_damage, card_description_en)
             SELECT
-                'd|' || code || '|' || $4 || '|' || $5 as code
+                l.id, l.en_url, en.name AS "en_name?", en.number AS "en_number?",
+                en.exp_code AS "en_exp_code?", l.printing_code, p.name AS "printing_name?",
+                p.number AS "printing_number?", p.expansion_code AS "printing_expansion_code?",
+                l.score, ARRAY_TO_STRING(l.signals, ' ') AS "signals!", l.status
+            FROM ptcg_en_name_links l
+            LEFT JOIN ptcg_en_cards en ON en.url = l.en_url
+            LEFT JOIN pokemon_trainer_printing p
+                ON p.region = l.region AND p.code = l.printing_code
+            WHERE l.status = $1
+            ORDER BY l.printing_code, l.score DESC
+            "#,
+            status.to_string()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(links)
+    }
+    /// Approving a link rejects the other pending links of the same printing
+    /// and names it after the EN card, unless it got a name meanwhile. Returns
+    /// how many printings were named.
+    pub async fn approve_en_name_links(&self, ids: &[i64]) -> Result<usize, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            "
+            UPDATE ptcg_en_name_links SET status = 'approved', reviewed_at = NOW()
+            WHERE id = ANY($1) AND status = 'pending'
+            ",
+            ids
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "
+            UPDATE ptcg_en_name_links SET status = 'rejected', reviewed_at = NOW()
+            WHERE status = 'pending' AND (region, printing_code) IN (
+                SELECT region, printing_code FROM ptcg_en_name_links WHERE id = ANY($1)
+            )
+            ",
+            ids
+        )
+        .execute(&mut *tx)
+        .await?;
+        let named = sqlx::query!(
+            "
+            UPDATE pokemon_trainer_printing p SET name_en = en.name
+            FROM ptcg_en_name_links l
+            JOIN ptcg_en_cards en ON en.url = l.en_url
+            WHERE l.id = ANY($1) AND l.status = 'approved'
+                AND p.region = l.region AND p.code = l.printing_code AND p.name_en IS NULL
+            RETURNING p.region, p.code, en.name
+            ",
+            ids
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for printing in &named {
+            let changes = [changed("name_en", None, Some(&printing.name))];
+            let key = format!("{}:{}", printing.region, printing.code);
+            record_revisions(&mut tx, "pokemon_trainer_printing", &key, changes).await?;
+        }
+        tx.commit().await?;
+        Ok(named.len())
+    }
+    pub async fn reject_en_name_links(&self, ids: &[i64]) -> Result<u64, RepositoryError> {
+        let rejected = sqlx::query!(
+            "
+            UPDATE ptcg_en_name_links SET status = 'rejected', reviewed_at = NOW()
+            WHERE id = ANY($1) AND status = 'pending'
+            ",
+            ids
+        )
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+        Ok(rejected)
+    }
+    pub async fn save_bigweb_cardset(
+        &self,
+        cardset: &BigwebCardset,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query!(
+            "
+            INSERT INTO bigweb_pokemon_expansion(id, bigweb_id, code, name, item_count)
+            VALUES(gen_random_uuid(), $1, $2, $3, $4)
+            ON CONFLICT(bigweb_id)
+            DO UPDATE SET code = $2, name = $3, item_count = $4, updated_at = NOW()
+            ",
+            cardset.id,
+            cardset.code.as_deref().unwrap_or_default(),
+            cardset.name,
+            cardset.item_count
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+    /// Upserts the set's products with their current price and appends every
+    /// price to the `card_prices` history.
+    pub async fn save_bigweb_products(
+        &self,
+        cardset: &BigwebCardset,
+        products: Vec<BigwebProduct>,
+    ) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        for product in products {
+            sqlx::query!(
+                "
+                INSERT INTO bigweb_pokemon_printing(
+                    id, bigweb_id, name, number, rarity, sale_price, remark, expansion_id,
+                    last_fetched_at
+                )
+                SELECT gen_random_uuid(), $1, $2, $3, $4, $5, $6, e.id, NOW()
+                FROM bigweb_pokemon_expansion e WHERE e.bigweb_id = $7
+                ON CONFLICT(bigweb_id)
+                DO UPDATE SET name = $2, number = $3, rarity = $4, sale_price = $5, remark = $6,
+                    last_fetched_at = NOW()
+                ",
+                product.id,
+                product.name,
+                product.number,
+                product.rarity,
+                product.price,
+                product.remark,
+                cardset.id
+            )
+            .execute(&mut *tx)
+            .await?;
+            let (Some(code), Some(price)) = (&cardset.code, product.price) else {
+                continue;
+            };
+            sqlx::query!(
+                "
+                INSERT INTO card_prices(
+                    game, set_code, number, name, source, card_key, price, currency, condition
+                )
+                VALUES('ptcg', $1, $2, $3, 'bigweb', $4, $5, 'JPY', $6)
+                ",
+                code,
+                product.number,
+                product.name,
+                product.id.to_string(),
+                price,
+                product.condition()
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query!(
+            "UPDATE bigweb_pokemon_expansion SET is_sync = true, updated_at = NOW() WHERE bigweb_id = $1",
+            cardset.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+    /// Prices observed since `since`, by card and then time.
+    pub async fn get_card_prices(
+        &self,
+        since: NaiveDate,
+    ) -> Result<Vec<PricePoint>, RepositoryError> {
+        let prices = sqlx::query_as!(
+            PricePoint,
+            r#"
+            SELECT game, set_code, source, card_key, name, price,
+                observed_at::DATE AS "observed_on!"
+            FROM card_prices
+            WHERE observed_at >= $1::DATE
+            ORDER BY source, card_key, observed_at, id
+            "#,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(prices)
+    }
+    pub async fn get_jp_expansion_features(
+        &self,
+    ) -> Result<Vec<ExpansionFeatures>, RepositoryError> {
+        let exps = sqlx::query_as!(
+            ExpansionFeatures,
+            r#"
+            SELECT jp.code, jp.release_date,
+                (SELECT COUNT(*) FROM tcg_collector tc WHERE tc.exp_code = jp.code) AS "card_count!"
+            FROM ptcg_jp_expansions jp
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(exps)
+    }
+    pub async fn get_expansion_features(
+        &self,
+        region: PtcgRegion,
+    ) -> Result<Vec<ExpansionFeatures>, RepositoryError> {
+        let exps = sqlx::query_as!(
+            ExpansionFeatures,
+            r#"
+            SELECT e.code, e.release_date,
+                (
+                    SELECT COUNT(*) FROM pokemon_trainer_fetchable_card f
+                    WHERE f.region = e.region AND f.expansion_code = e.code
+                        AND f.removed_at IS NULL
+                ) AS "card_count!"
+            FROM pokemon_trainer_expansion e
+            WHERE e.region = $1
+            "#,
+            region.to_string()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(exps)
+    }
+    /// Manual mappings are never replaced by suggestions.
+    pub async fn suggest_expansion_mapping(
+        &self,
+        region: PtcgRegion,
+        jp_code: &str,
+        tw_code: &str,
+        score: f32,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query!(
+            "
+            INSERT INTO ptcg_expansion_map(region, jp_code, tw_code, score)
+            VALUES($1, $2, $3, $4)
+            ON CONFLICT(region, jp_code)
+            DO UPDATE SET tw_code = $3, score = $4, updated_at = NOW()
+            WHERE ptcg_expansion_map.source = 'auto'
+            ",
+            region.to_string(),
+            jp_code,
+            tw_code,
+            score
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+    pub async fn set_expansion_mapping(
+        &self,
+        region: PtcgRegion,
+        jp_code: &str,
+        tw_code: &str,
+        en_code: Option<&str>,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query!(
+            "
+            INSERT INTO ptcg_expansion_map(region, jp_code, tw_code, en_code, source)
+            VALUES($1, $2, $3, $4, 'manual')
+            ON CONFLICT(region, jp_code)
+            DO UPDATE SET tw_code = $3, en_code = $4, score = NULL, source = 'manual',
+                en_score = NULL, updated_at = NOW()
+            ",
+            region.to_string(),
+            jp_code,
+            tw_code,
+            en_code
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+    pub async fn get_expansion_map(
+        &self,
+        region: PtcgRegion,
+    ) -> Result<Vec<PtcgExpansionMapping>, RepositoryError> {
+        let map = sqlx::query_as!(
+            PtcgExpansionMapping,
+            "
+            SELECT m.region, m.jp_code, jp.name_en AS jp_name, m.tw_code, tw.name AS tw_name,
+                m.en_code, m.score, m.source, m.en_score
+            FROM ptcg_expansion_map m
+            JOIN ptcg_jp_expansions jp ON jp.code = m.jp_code
+            JOIN pokemon_trainer_expansion tw ON tw.region = m.region AND tw.code = m.tw_code
+            WHERE m.region = $1
+            ORDER BY jp.release_date DESC
+            ",
+            region.to_string()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(map)
+    }
+    /// Mapped JP expansions with the English names of their cards.
+    pub async fn get_mapped_jp_expansion_names(
+        &self,
+    ) -> Result<Vec<ExpansionNames>, RepositoryError> {
+        let exps = sqlx::query_as!(
+            ExpansionNames,
+            r#"
+            SELECT jp.code, jp.release_date,
+                ARRAY(SELECT DISTINCT tc.name FROM tcg_collector tc WHERE tc.exp_code = jp.code)
+                    AS "names!"
+            FROM ptcg_jp_expansions jp
+            WHERE jp.code IN (SELECT jp_code FROM ptcg_expansion_map)
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(exps)
+    }
+    pub async fn get_en_expansion_names(&self) -> Result<Vec<ExpansionNames>, RepositoryError> {
+        let exps = sqlx::query_as!(
+            ExpansionNames,
+            r#"
+            SELECT en.code, en.release_date,
+                ARRAY(SELECT DISTINCT c.name FROM ptcg_en_cards c WHERE c.exp_code = en.code)
+                    AS "names!"
+            FROM ptcg_en_expansions en
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(exps)
+    }
+    /// Sets the EN expansion of every mapping of `jp_code`, unless one was set
+    /// by hand.
+    pub async fn suggest_en_code(
+        &self,
+        jp_code: &str,
+        en_code: &str,
+        score: f32,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query!(
+            "
+            UPDATE ptcg_expansion_map SET en_code = $2, en_score = $3, updated_at = NOW()
+            WHERE jp_code = $1 AND (en_code IS NULL OR en_score IS NOT NULL)
+            ",
+            jp_code,
+            en_code,
+            score
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+    pub async fn get_tw_expansion_code(
+        &self,
+        jp_code: &str,
+    ) -> Result<Option<String>, RepositoryError> {
+        let mapping = sqlx::query!(
+            "SELECT tw_code FROM ptcg_expansion_map WHERE region = 'tw' AND jp_code = $1",
+            jp_code
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(mapping.map(|m| m.tw_code))
+    }
+    pub async fn ptcg_tw_is_exists(
+        &self,
+        number: &str,
+        tw_exp_code: &str,
+    ) -> Result<bool, RepositoryError> {
+        let printing = sqlx::query!(
+            "
+            SELECT code FROM pokemon_trainer_printing
+            WHERE number = $1 AND region = 'tw' AND expansion_code = $2
+            LIMIT 1
+            ",
+            number,
+            tw_exp_code
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(printing.is_some())
+    }
+    pub async fn get_extra_sources(
+        &self,
+        name_en: &str,
+    ) -> Result<Vec<PtcgExtraSource>, RepositoryError> {
+        let sources = sqlx::query_as!(
+            PtcgExtraSource,
+            "
+            SELECT code, skill1_name_en, skill1_damage, card_description_en
             FROM pokemon_trainer_printing
-            WHERE name_en = $1 AND (skill1_name_en = $2 OR card_description_en = $3)
-            LIMIT 1"#,
+            WHERE region = 'tw' AND name_en = $1 AND code NOT LIKE 'd|%'
+            ",
+            name_en
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(sources)
+    }
+    /// Proposes `card` as another version of the TW printing `source`, in the
+    /// TW expansion `tw_exp_code`. Already reviewed candidates are left alone.
+    pub async fn upsert_extra_candidate(
+        &self,
+        card: &PtcgJpCard,
+        tw_exp_code: &str,
+        source: &PtcgExtraSource,
+        confidence: f32,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query!(
+            "
+            INSERT INTO ptcg_extra_candidates(
+                code, source_code, source_url, name_en, number, expansion_code, rarity, confidence)
+            VALUES('d|' || $1 || '|' || $4 || '|' || $5, $1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT(code)
+            DO UPDATE SET source_url = $2, name_en = $3, rarity = $6, confidence = $7
+            WHERE ptcg_extra_candidates.status = 'pending'
+            ",
+            source.code,
+            card.url,
             card.name,
-            card.skill1_name_en,
-            card.desc,
             card.number,
-            card.exp_code,
+            tw_exp_code,
+            card.rarity.clone() as Option<PtcgRarity>,
+            confidence,
         )
-        .fetch_all(&self.pool)
+        .execute(&self.pool)
         .await?;
-        dbg!(card);
-        dbg!(results);
         Ok(())
     }
+    pub async fn get_extra_candidates(
+        &self,
+        status: ReviewStatus,
+    ) -> Result<Vec<PtcgExtraCandidate>, RepositoryError> {
+        let candidates = sqlx::query_as!(
+            PtcgExtraCandidate,
+            "
+            SELECT id, code, source_code, source_url, name_en, number, expansion_code, confidence, status
+            FROM ptcg_extra_candidates
+            WHERE status = $1
+            ORDER BY confidence DESC, id
+            ",
+            status.to_string()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(candidates)
+    }
+    /// Copies pending candidates into `pokemon_trainer_printing`, taking the
+    /// gameplay fields from the matched TW printing, and marks them approved.
+    /// Returns the ids approved; a candidate whose printing conflicts with an
+    /// existing row, or whose expansion is not a TW expansion, stays pending.
+    pub async fn approve_extra_candidates(&self, ids: &[i64]) -> Result<Vec<i64>, RepositoryError> {
+        let approved = sqlx::query_scalar!(
+            "
+            WITH inserted AS (
+                INSERT INTO pokemon_trainer_printing(
+                    code, name, kind, number, rarity, expansion_code, name_en, skill1_name_en,
+                    skill1_damage, card_description_en, evolve_marker, hp, weak_point, resist,
+                    escape, energy, region)
+                SELECT c.code, src.name, src.kind, c.number, c.rarity::TEXT, c.expansion_code,
+                    c.name_en, src.skill1_name_en, src.skill1_damage, src.card_description_en,
+                    src.evolve_marker, src.hp, src.weak_point, src.resist, src.escape, src.energy,
+                    'tw'
+                FROM ptcg_extra_candidates c
+                JOIN pokemon_trainer_printing src ON src.region = 'tw' AND src.code = c.source_code
+                JOIN pokemon_trainer_expansion e ON e.region = 'tw' AND e.code = c.expansion_code
+                WHERE c.id = ANY($1) AND c.status = 'pending'
+                ON CONFLICT DO NOTHING
+                RETURNING code
+            )
+            UPDATE ptcg_extra_candidates SET status = 'approved', reviewed_at = NOW()
+            WHERE id = ANY($1) AND code IN (SELECT code FROM inserted)
+            RETURNING id
+            ",
+            ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(approved)
+    }
+    pub async fn reject_extra_candidates(&self, ids: &[i64]) -> Result<u64, RepositoryError> {
+        let rejected = sqlx::query!(
+            "
+            UPDATE ptcg_extra_candidates SET status = 'rejected', reviewed_at = NOW()
+            WHERE id = ANY($1) AND status = 'pending'
+            ",
+            ids
+        )
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+        Ok(rejected)
+    }
     pub async fn save_ptcg_jp_cards(&self, cards: Vec<PtcgJpCard>) -> Result<(), RepositoryError> {
         for card in cards {
             dbg!(&card);
-            sqlx::query!(
+            let mut tx = self.pool.begin().await?;
+            let updated = sqlx::query!(
                 "
-                UPDATE pokemon_trainer_printing SET
+                UPDATE pokemon_trainer_printing p SET
                 name_en = $1,
                 skill1_name_en = $4,
                 skill1_damage = $5,
                 card_description_en = $6
-                WHERE number = $2 AND expansion_code = $3
+                FROM (
+                    SELECT code, name_en, skill1_name_en, skill1_damage, card_description_en
+                    FROM pokemon_trainer_printing
+                    WHERE region = 'tw' AND (
+                        code IN (
+                            SELECT printing_code FROM ptcg_card_links
+                            WHERE tc_url = $7 AND region = 'tw' AND status = 'approved'
+                        )
+                        OR (
+                            NOT EXISTS (
+                                SELECT 1 FROM ptcg_card_links
+                                WHERE tc_url = $7 AND region = 'tw' AND status = 'approved'
+                            )
+                            AND number = $2 AND expansion_code IN (
+                                SELECT tw_code FROM ptcg_expansion_map
+                                WHERE region = 'tw' AND jp_code = $3
+                            )
+                        )
+                    )
+                    FOR UPDATE
+                ) old
+                WHERE p.region = 'tw' AND p.code = old.code
+                RETURNING p.code, old.name_en AS old_name_en, old.skill1_name_en AS old_skill1_name_en,
+                    old.skill1_damage AS old_skill1_damage,
+                    old.card_description_en AS old_card_description_en
                 ",
                 card.name,
                 card.number,
@@ -190,87 +925,437 @@ impl Repository {
                 card.skill1_name_en,
                 card.skill1_damage,
                 card.desc,
+                card.url,
             )
-            .execute(&self.pool)
+            .fetch_all(&mut *tx)
             .await?;
+            for old in updated {
+                let changes = [
+                    changed("name_en", old.old_name_en, Some(&card.name)),
+                    changed(
+                        "skill1_name_en",
+                        old.old_skill1_name_en,
+                        card.skill1_name_en.as_deref(),
+                    ),
+                    changed(
+                        "skill1_damage",
+                        old.old_skill1_damage,
+                        card.skill1_damage.as_deref(),
+                    ),
+                    changed(
+                        "card_description_en",
+                        old.old_card_description_en,
+                        card.desc.as_deref(),
+                    ),
+                ];
+                let key = format!("tw:{}", old.code);
+                record_revisions(&mut tx, "pokemon_trainer_printing", &key, changes).await?;
+            }
+            tx.commit().await?;
         }
         Ok(())
     }
-    pub async fn upsert_ptcg_expansion(&self, exp: &PtcgExpansion) -> Result<(), RepositoryError> {
+    /// TW printings that could be the same card as a JP printing: those in the
+    /// mapped expansion, or sharing its English name.
+    pub async fn get_link_candidates(
+        &self,
+        jp_exp_code: &str,
+        name_en: &str,
+    ) -> Result<Vec<(String, CardFeatures)>, RepositoryError> {
+        let printings = sqlx::query!(
+            r#"
+            SELECT code, expansion_code, number, name_en, rarity, hp
+            FROM pokemon_trainer_printing
+            WHERE region = 'tw' AND code NOT LIKE 'd|%' AND (
+                expansion_code IN (
+                    SELECT tw_code FROM ptcg_expansion_map WHERE region = 'tw' AND jp_code = $1
+                )
+                OR name_en = $2
+            )
+            "#,
+            jp_exp_code,
+            name_en
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let codes: Vec<_> = printings.iter().map(|p| p.code.clone()).collect();
+        let attacks = sqlx::query!(
+            "
+            SELECT printing_code, cost, damage FROM pokemon_trainer_attack
+            WHERE region = 'tw' AND printing_code = ANY($1)
+            ORDER BY printing_code, position
+            ",
+            &codes
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let candidates = printings
+            .into_iter()
+            .map(|p| {
+                let attacks = attacks
+                    .iter()
+                    .filter(|a| a.printing_code == p.code)
+                    .map(|a| AttackFeature::new(a.cost.clone(), a.damage.as_deref()))
+                    .collect();
+                let features = CardFeatures {
+                    expansion_code: p.expansion_code,
+                    number: p.number,
+                    name: p.name_en,
+                    rarity: p.rarity,
+                    hp: p.hp,
+                    attacks,
+                };
+                (p.code, features)
+            })
+            .collect();
+        Ok(candidates)
+    }
+    /// Links already reviewed keep their status and score.
+    pub async fn upsert_card_link(
+        &self,
+        tc_url: &str,
+        printing_code: &str,
+        link: &LinkScore,
+    ) -> Result<(), RepositoryError> {
+        let signals: Vec<String> = link.signals.iter().map(|s| s.to_string()).collect();
         sqlx::query!(
-              "INSERT INTO pokemon_trainer_expansion(id, code, series, name, release_date, updated_at)
-              VALUES(gen_random_uuid(), $1, $2, $3, $4, NOW())
-              ON CONFLICT(code)
-              DO UPDATE SET code = $1, series = $2, name = $3, release_date = $4, updated_at = NOW()",
-              exp.code, exp.series, exp.name, exp.release_date)
+            "
+            INSERT INTO ptcg_card_links(tc_url, printing_code, score, signals)
+            VALUES($1, $2, $3, $4)
+            ON CONFLICT(tc_url, region, printing_code)
+            DO UPDATE SET score = $3, signals = $4
+            WHERE ptcg_card_links.status = 'pending'
+            ",
+            tc_url,
+            printing_code,
+            link.score,
+            &signals
+        )
         .execute(&self.pool)
         .await?;
         Ok(())
     }
+    pub async fn get_card_links(
+        &self,
+        status: ReviewStatus,
+    ) -> Result<Vec<PtcgCardLink>, RepositoryError> {
+        let links = sqlx::query_as!(
+            PtcgCardLink,
+            r#"
+            SELECT
+                l.id, l.tc_url, tc.name AS "tc_name?", tc.number AS "tc_number?",
+                tc.exp_code AS "tc_exp_code?", l.printing_code, p.name AS "printing_name?",
+                p.number AS "printing_number?", p.expansion_code AS "printing_expansion_code?",
+                l.score, ARRAY_TO_STRING(l.signals, ' ') AS "signals!", l.status
+            FROM ptcg_card_links l
+            LEFT JOIN tcg_collector tc ON tc.url = l.tc_url
+            LEFT JOIN pokemon_trainer_printing p
+                ON p.region = l.region AND p.code = l.printing_code
+            WHERE l.status = $1
+            ORDER BY l.tc_url, l.score DESC
+            "#,
+            status.to_string()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(links)
+    }
+    /// Approving a link rejects the other pending links of the same JP card.
+    pub async fn approve_card_links(&self, ids: &[i64]) -> Result<u64, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        let approved = sqlx::query!(
+            "
+            UPDATE ptcg_card_links SET status = 'approved', reviewed_at = NOW()
+            WHERE id = ANY($1) AND status = 'pending'
+            ",
+            ids
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        sqlx::query!(
+            "
+            UPDATE ptcg_card_links SET status = 'rejected', reviewed_at = NOW()
+            WHERE status = 'pending' AND tc_url IN (
+                SELECT tc_url FROM ptcg_card_links WHERE id = ANY($1)
+            )
+            ",
+            ids
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(approved)
+    }
+    pub async fn reject_card_links(&self, ids: &[i64]) -> Result<u64, RepositoryError> {
+        let rejected = sqlx::query!(
+            "
+            UPDATE ptcg_card_links SET status = 'rejected', reviewed_at = NOW()
+            WHERE id = ANY($1) AND status = 'pending'
+            ",
+            ids
+        )
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+        Ok(rejected)
+    }
+    /// Returns whether the expansion was new.
+    pub async fn upsert_ptcg_expansion(
+        &self,
+        region: PtcgRegion,
+        exp: &PtcgExpansion,
+    ) -> Result<bool, RepositoryError> {
+        let r = sqlx::query!(
+              r#"INSERT INTO pokemon_trainer_expansion(id, code, series, name, release_date, updated_at, last_seen_at, region)
+              VALUES(gen_random_uuid(), $1, $2, $3, $4, NOW(), NOW(), $5)
+              ON CONFLICT(region, code)
+              DO UPDATE SET code = $1, series = $2, name = $3, release_date = $4, updated_at = NOW(), last_seen_at = NOW()
+              RETURNING (xmax = 0) AS "inserted!""#,
+              exp.code, exp.series, exp.name, exp.release_date, region.to_string())
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(r.inserted)
+    }
     pub async fn upsert_fetchable(
         &self,
+        region: PtcgRegion,
         fetchable_codes: Vec<String>,
         set_code: &str,
     ) -> Result<(), RepositoryError> {
         for code in fetchable_codes {
             sqlx::query!(
-                "INSERT INTO pokemon_trainer_fetchable_card(code, fetched, expansion_code) VALUES($1, False, $2)
-                ON CONFLICT(code)
+                "INSERT INTO pokemon_trainer_fetchable_card(code, fetched, expansion_code, region) VALUES($1, False, $2, $3)
+                ON CONFLICT(region, code)
                 DO UPDATE
                     SET code = $1, fetched = False, expansion_code = $2",
                 code,
-                set_code
+                set_code,
+                region.to_string()
             )
             .execute(&self.pool)
             .await?;
         }
         Ok(())
     }
-    pub fn get_fetchable(&self) -> BoxStream<Result<(String, String), RepositoryError>> {
+    /// Diffs the live card codes of an expansion against the queue: new codes
+    /// are enqueued, known ones get `last_seen_at` bumped and codes missing from
+    /// the site are flagged with `removed_at`. Returns (new, removed) counts.
+    /// An empty `live_codes` changes nothing rather than flagging every card.
+    pub async fn refresh_fetchable(
+        &self,
+        region: PtcgRegion,
+        live_codes: &[String],
+        set_code: &str,
+    ) -> Result<(usize, u64), RepositoryError> {
+        if live_codes.is_empty() {
+            return Ok((0, 0));
+        }
+        let mut tx = self.pool.begin().await?;
+        let rows = sqlx::query!(
+            r#"
+            INSERT INTO pokemon_trainer_fetchable_card(code, fetched, expansion_code, region)
+            SELECT code, False, $2, $3 FROM UNNEST($1::TEXT[]) AS code
+            ON CONFLICT(region, code)
+            DO UPDATE SET last_seen_at = NOW(), removed_at = NULL,
+                updated_at = CASE
+                    WHEN pokemon_trainer_fetchable_card.removed_at IS NULL
+                    THEN pokemon_trainer_fetchable_card.updated_at
+                    ELSE NOW()
+                END
+            RETURNING (xmax = 0) AS "inserted!"
+            "#,
+            live_codes,
+            set_code,
+            region.to_string()
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let new = rows.iter().filter(|r| r.inserted).count();
+        let removed = sqlx::query!(
+            "
+            UPDATE pokemon_trainer_fetchable_card
+            SET removed_at = NOW(), updated_at = NOW()
+            WHERE region = $1 AND expansion_code = $2 AND removed_at IS NULL
+                AND NOT (code = ANY($3))
+            ",
+            region.to_string(),
+            set_code,
+            live_codes
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        tx.commit().await?;
+        Ok((new, removed))
+    }
+    pub fn get_fetchable(
+        &self,
+        region: PtcgRegion,
+    ) -> BoxStream<'_, Result<(String, String), RepositoryError>> {
         sqlx::query!(
             "SELECT fetchable.code, fetchable.expansion_code
             FROM pokemon_trainer_fetchable_card fetchable
-            WHERE fetched = false
-            "
+            WHERE fetched = false AND region = $1
+            ",
+            region.to_string()
         )
         .fetch(&self.pool)
         .map_ok(|s| (s.code, s.expansion_code))
         .map_err(RepositoryError::from)
         .boxed()
     }
-    pub fn get_fetchable_by_code(&self, code: &str) -> BoxStream<Result<String, RepositoryError>> {
+    pub fn get_fetchable_by_code(
+        &self,
+        region: PtcgRegion,
+        code: &str,
+    ) -> BoxStream<'_, Result<String, RepositoryError>> {
         sqlx::query!(
-            "SELECT code FROM pokemon_trainer_fetchable_card WHERE expansion_code = $1",
-            code
+            "SELECT code FROM pokemon_trainer_fetchable_card WHERE expansion_code = $1 AND region = $2",
+            code,
+            region.to_string()
         )
         .fetch(&self.pool)
         .map_ok(|s| s.code)
         .map_err(RepositoryError::from)
         .boxed()
     }
-    pub async fn upsert_the_ptcg_card(&self, card: &ThePTCGCard) {
+    pub async fn upsert_the_ptcg_card(
+        &self,
+        region: PtcgRegion,
+        card: &ThePTCGCard,
+    ) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        let old = sqlx::query!(
+            "
+            SELECT name, kind, number, expansion_code, evolve_marker, img_src, hp, weak_point,
+                resist, escape, expansion_symbol, energy, artist
+            FROM pokemon_trainer_printing
+            WHERE region = $1 AND code = $2
+            FOR UPDATE
+            ",
+            region.to_string(),
+            card.code
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(old) = old {
+            let changes = [
+                changed("name", Some(old.name), Some(&card.name)),
+                changed("kind", Some(old.kind), Some(&card.kind)),
+                changed("number", Some(old.number), card.number.as_deref()),
+                changed(
+                    "expansion_code",
+                    Some(old.expansion_code),
+                    card.set_code.as_deref(),
+                ),
+                changed(
+                    "evolve_marker",
+                    old.evolve_marker,
+                    card.evolve_marker.as_deref(),
+                ),
+                changed("img_src", old.img_src, card.img_src.as_deref()),
+                changed("hp", old.hp, card.hp.as_deref()),
+                changed("weak_point", old.weak_point, card.weak_point.as_deref()),
+                changed("resist", old.resist, card.resist.as_deref()),
+                changed("escape", old.escape, card.escape.as_deref()),
+                changed(
+                    "expansion_symbol",
+                    old.expansion_symbol,
+                    card.expansion_symbol.as_deref(),
+                ),
+                changed("energy", old.energy, card.energy.as_deref()),
+                changed("artist", old.artist, Some(&card.artist)),
+            ];
+            let key = format!("{region}:{}", card.code);
+            record_revisions(&mut tx, "pokemon_trainer_printing", &key, changes).await?;
+        }
         sqlx::query!(
             "
-                   INSERT INTO pokemon_trainer_printing(code, kind, name, number, expansion_code)
-                   VALUES($1, $2, $3, $4, $5)
-                   ON CONFLICT(name, number, expansion_code)
+                   INSERT INTO pokemon_trainer_printing(
+                       code, kind, name, number, expansion_code, evolve_marker, img_src, hp,
+                       weak_point, resist, escape, expansion_symbol, energy, artist, rules, region)
+                   VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+                   ON CONFLICT(region, code)
                    DO UPDATE
-                   SET kind = $2, name = $3, number = $4, expansion_code = $5
+                   SET kind = $2, name = $3, number = $4, expansion_code = $5,
+                       evolve_marker = $6, img_src = $7, hp = $8, weak_point = $9, resist = $10,
+                       escape = $11, expansion_symbol = $12, energy = $13, artist = $14,
+                       rules = $15, last_fetched_at = NOW()
                    ",
             card.code,
             card.kind,
             card.name,
             card.number,
-            card.set_code
+            card.set_code,
+            card.evolve_marker,
+            card.img_src,
+            card.hp,
+            card.weak_point,
+            card.resist,
+            card.escape,
+            card.expansion_symbol,
+            card.energy,
+            card.artist,
+            &card.rules,
+            region.to_string(),
         )
-        .execute(&self.pool)
-        .await
-        .unwrap();
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM pokemon_trainer_attack WHERE printing_code = $1 AND region = $2",
+            card.code,
+            region.to_string()
+        )
+        .execute(&mut *tx)
+        .await?;
+        for (position, attack) in card.attacks.iter().enumerate() {
+            sqlx::query!(
+                "
+                INSERT INTO pokemon_trainer_attack(printing_code, position, name, cost, damage, effect, region)
+                VALUES($1, $2, $3, $4, $5, $6, $7)
+                ",
+                card.code,
+                position as i32,
+                attack.name,
+                &attack.cost,
+                attack.damage,
+                attack.effect,
+                region.to_string(),
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query!(
+            "DELETE FROM pokemon_trainer_ability WHERE printing_code = $1 AND region = $2",
+            card.code,
+            region.to_string()
+        )
+        .execute(&mut *tx)
+        .await?;
+        for (position, ability) in card.abilities.iter().enumerate() {
+            sqlx::query!(
+                "
+                INSERT INTO pokemon_trainer_ability(printing_code, position, name, effect, region)
+                VALUES($1, $2, $3, $4, $5)
+                ",
+                card.code,
+                position as i32,
+                ability.name,
+                ability.effect,
+                region.to_string(),
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
     }
-    pub async fn fetched(&self, code: &str) {
+    pub async fn fetched(&self, region: PtcgRegion, code: &str) {
         sqlx::query!(
-            "UPDATE pokemon_trainer_fetchable_card SET fetched = True WHERE code = $1",
-            code
+            "UPDATE pokemon_trainer_fetchable_card SET fetched = True WHERE code = $1 AND region = $2",
+            code,
+            region.to_string()
         )
         .execute(&self.pool)
         .await
@@ -278,14 +1363,64 @@ impl Repository {
     }
     pub async fn update_the_ptcg_rarity(
         &self,
+        region: PtcgRegion,
+        ids: Vec<String>,
+        rarity: &PtcgRarity,
+    ) -> Result<(), RepositoryError> {
+        dbg!(rarity.to_string());
+        let mut tx = self.pool.begin().await?;
+        let updated = sqlx::query!(
+            "
+            UPDATE pokemon_trainer_printing p SET rarity = $1
+            FROM (
+                SELECT code, rarity FROM pokemon_trainer_printing
+                WHERE code = ANY($2) AND region = $3
+                FOR UPDATE
+            ) old
+            WHERE p.region = $3 AND p.code = old.code
+            RETURNING p.code, old.rarity AS old_rarity
+            ",
+            rarity.to_string(),
+            &ids,
+            region.to_string()
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for old in updated {
+            let changes = [changed("rarity", old.old_rarity, Some(&rarity.to_string()))];
+            let key = format!("{region}:{}", old.code);
+            record_revisions(&mut tx, "pokemon_trainer_printing", &key, changes).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+    pub async fn update_the_ptcg_regulation(
+        &self,
+        region: PtcgRegion,
+        ids: Vec<String>,
+        mark: &str,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query!(
+            "UPDATE pokemon_trainer_printing SET regulation_mark = $1 WHERE code = ANY($2) AND region = $3",
+            mark,
+            &ids,
+            region.to_string()
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+    pub async fn update_the_ptcg_energy_type(
+        &self,
+        region: PtcgRegion,
         ids: Vec<String>,
-        rarity: &PtcgRarity,
+        energy_type: &str,
     ) -> Result<(), RepositoryError> {
-        dbg!(rarity.to_string());
         sqlx::query!(
-            "UPDATE pokemon_trainer_printing SET rarity = $1 WHERE code = ANY($2)",
-            rarity.to_string(),
-            &ids
+            "UPDATE pokemon_trainer_printing SET energy_type = $1 WHERE code = ANY($2) AND region = $3",
+            energy_type,
+            &ids,
+            region.to_string()
         )
         .execute(&self.pool)
         .await?;
@@ -300,7 +1435,7 @@ impl Repository {
         .await
         .unwrap();
     }
-    pub async fn get_yugioh_expansion_link(&self) -> Option<ExpansionLink> {
+    pub async fn get_yugioh_expansion_link(&self) -> Option<ExpansionLink<'_>> {
         let mut conn = self.pool.begin().await.unwrap();
         let link = sqlx::query!(
             "
@@ -316,7 +1451,7 @@ impl Repository {
             url: link.url,
         })
     }
-    pub async fn get_yugioh_printing_link(&self) -> Option<PrintingLink> {
+    pub async fn get_yugioh_printing_link(&self) -> Option<PrintingLink<'_>> {
         let mut conn = self.pool.begin().await.unwrap();
         let link =
             sqlx::query!("SELECT url FROM yugioh_printing_link LIMIT 1 FOR UPDATE SKIP LOCKED")
@@ -337,7 +1472,47 @@ impl Repository {
         .await
         .unwrap();
     }
-    pub async fn upsert_yugioh_printing_detail(&self, detail: YugiohPrinting) {
+    pub async fn upsert_yugioh_printing_detail(
+        &self,
+        detail: YugiohPrinting,
+    ) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        let old = sqlx::query!(
+            "
+            SELECT name_jp, name_en, number, release_date, remark, expansion_code
+            FROM yugioh_printing_detail
+            WHERE card_id = $1 AND expansion_name = $2 AND rarity = $3
+            FOR UPDATE
+            ",
+            detail.card_id,
+            detail.expansion_name,
+            detail.rarity
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(old) = old {
+            let changes = [
+                changed("name_jp", Some(old.name_jp), Some(&detail.name_jp)),
+                changed("name_en", Some(old.name_en), Some(&detail.name_en)),
+                changed("number", Some(old.number), Some(&detail.number)),
+                changed(
+                    "release_date",
+                    Some(old.release_date),
+                    Some(&detail.release_date),
+                ),
+                changed("remark", old.remark, Some(&detail.remark)),
+                changed(
+                    "expansion_code",
+                    Some(old.expansion_code),
+                    Some(&detail.r#ref),
+                ),
+            ];
+            let key = format!(
+                "{}:{}:{}",
+                detail.card_id, detail.expansion_name, detail.rarity
+            );
+            record_revisions(&mut tx, "yugioh_printing_detail", &key, changes).await?;
+        }
         sqlx::query!(
             "
             INSERT INTO yugioh_printing_detail(
@@ -345,7 +1520,8 @@ impl Repository {
             VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9)
             ON CONFLICT(card_id, expansion_name, rarity)
             DO UPDATE
-            SET name_jp = $1, name_en = $2, number = $4, release_date = $5, remark = $6, expansion_code = $7
+            SET name_jp = $1, name_en = $2, number = $4, release_date = $5, remark = $6, expansion_code = $8,
+                last_fetched_at = NOW()
             ",
             detail.name_jp,
             detail.name_en,
@@ -357,56 +1533,127 @@ impl Repository {
             detail.r#ref,
             detail.card_id
         )
-        .execute(&self.pool)
-        .await
-        .unwrap();
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
     }
     pub async fn get_yugioh_printing(&self) -> Result<Vec<YugiohPrinting>, RepositoryError> {
-        let printings = sqlx::query!("SELECT * FROM yugioh_printing_detail")
-            .fetch_all(&self.pool)
-            .await?
-            .into_iter()
-            .map(|record| YugiohPrinting {
-                card_id: record.card_id,
-                name_jp: record.name_jp,
-                name_en: record.name_en,
-                rarity: record.rarity,
-                number: record.number,
-                release_date: record.release_date,
-                remark: record.remark.unwrap_or(String::from("")),
-                expansion_name: record.expansion_name,
-                r#ref: record.expansion_code,
-            })
-            .collect();
+        let printings = sqlx::query!(
+            r#"
+            SELECT card_id, name_jp, name_en, rarity, number, release_date, remark, expansion_name,
+                expansion_code, last_fetched_at AS "last_fetched_at: OffsetDateTime"
+            FROM yugioh_printing_detail
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|record| YugiohPrinting {
+            card_id: record.card_id,
+            name_jp: record.name_jp,
+            name_en: record.name_en,
+            rarity: record.rarity,
+            number: record.number,
+            release_date: record.release_date,
+            remark: record.remark.unwrap_or(String::from("")),
+            expansion_name: record.expansion_name,
+            r#ref: record.expansion_code,
+            last_fetched_at: record.last_fetched_at.into(),
+        })
+        .collect();
         Ok(printings)
     }
 
-    pub(crate) fn get_ptcg_codes(&self) -> BoxStream<Result<String, RepositoryError>> {
-        sqlx::query!("SELECT code FROM pokemon_trainer_expansion")
-            .fetch(&self.pool)
-            .map_ok(|c| c.code)
-            .map_err(|e| e.into())
-            .boxed()
+    pub(crate) fn get_ptcg_codes(
+        &self,
+        region: PtcgRegion,
+    ) -> BoxStream<'_, Result<String, RepositoryError>> {
+        sqlx::query!(
+            "SELECT code FROM pokemon_trainer_expansion WHERE region = $1",
+            region.to_string()
+        )
+        .fetch(&self.pool)
+        .map_ok(|c| c.code)
+        .map_err(|e| e.into())
+        .boxed()
+    }
+
+    pub(crate) fn get_ptcg_printing_codes(
+        &self,
+        region: PtcgRegion,
+    ) -> BoxStream<'_, Result<String, RepositoryError>> {
+        sqlx::query!(
+            "SELECT code FROM pokemon_trainer_printing WHERE region = $1 AND code NOT LIKE 'd|%'",
+            region.to_string()
+        )
+        .fetch(&self.pool)
+        .map_ok(|c| c.code)
+        .map_err(|e| e.into())
+        .boxed()
     }
 
-    pub(crate) fn get_all_pokemon_trainer_printing(&self) -> BoxStream<PokemonCard> {
+    /// `sale_price` is the lowest latest price among undamaged products of the
+    /// printing's set and number, since a source can list several variants of
+    /// one card.
+    pub(crate) fn get_all_pokemon_trainer_printing(
+        &self,
+        region: PtcgRegion,
+    ) -> BoxStream<'_, PokemonCard> {
         sqlx::query!(
-            "SELECT
-            p.code as id,
-            p.name as name,
-            p.number as number,
-            NULL::bigint as sale_price,
+            r#"SELECT
+            p.code as "id!",
+            p.name as "name!",
+            p.number as "number!",
+            price.price as "sale_price?",
             p.rarity as rarity,
-            e.code as set_id,
-            e.name as set_name,
-            e.code as set_ref,
-            NULL as remark
+            e.code as "set_id?",
+            e.name as "set_name?",
+            e.code as "set_ref!",
+            NULL as remark,
+            p.hp as hp,
+            p.energy_type as energy_type,
+            p.energy as energy,
+            p.artist as artist,
+            p.last_fetched_at as "last_fetched_at: OffsetDateTime",
+            wiki.name_chinese as "name_chinese?",
+            p.name_en
             FROM pokemon_trainer_printing p
-            LEFT JOIN pokemon_trainer_expansion e ON p.expansion_code = e.code"
+            LEFT JOIN pokemon_trainer_expansion e
+                ON p.expansion_code = e.code AND p.region = e.region
+            LEFT JOIN LATERAL (
+                SELECT w.name_chinese FROM pokewiki w
+                WHERE w.name_chinese IS NOT NULL
+                    AND LTRIM(SPLIT_PART(w.number, '/', 1), '0')
+                        = LTRIM(SPLIT_PART(p.number, '/', 1), '0')
+                    AND (LOWER(w.exp_code) = LOWER(p.expansion_code) OR LOWER(w.exp_code) IN (
+                        SELECT m.jp_code FROM ptcg_expansion_map m
+                        WHERE m.region = p.region AND m.tw_code = p.expansion_code
+                    ))
+                LIMIT 1
+            ) wiki ON true
+            LEFT JOIN LATERAL (
+                SELECT MIN(latest.price) AS price FROM (
+                    SELECT DISTINCT ON (cp.source, cp.card_key) cp.price
+                    FROM card_prices cp
+                    WHERE cp.game = 'ptcg' AND cp.condition = 'normal'
+                        AND LTRIM(SPLIT_PART(cp.number, '/', 1), '0')
+                            = LTRIM(SPLIT_PART(p.number, '/', 1), '0')
+                        AND (LOWER(cp.set_code) = LOWER(p.expansion_code) OR LOWER(cp.set_code) IN (
+                            SELECT m.jp_code FROM ptcg_expansion_map m
+                            WHERE m.region = p.region AND m.tw_code = p.expansion_code
+                        ))
+                    ORDER BY cp.source, cp.card_key, cp.observed_at DESC
+                ) latest
+            ) price ON true
+            WHERE p.region = $1"#,
+            region.to_string()
         )
         .fetch(&self.pool)
-        .filter_map(|r| async {
-            let record = r.ok()?;
+        .filter_map(move |r| async move {
+            let record = r
+                .map_err(|e| warn!("ptcg printing left out of the export: {e}"))
+                .ok()?;
             Some(PokemonCard {
                 id: record.id,
                 set_id: record.set_id.unwrap(),
@@ -414,61 +1661,101 @@ impl Repository {
                 name: record.name,
                 number: Some(record.number),
                 set_ref: record.set_ref,
+                region,
                 sale_price: record.sale_price,
                 rarity: record.rarity,
                 remark: record.remark,
-                last_fetched_at: LastFetchedAt::default(),
+                hp: record.hp,
+                // the symbol only stands in until the energy walk has run
+                energy_type: record
+                    .energy_type
+                    .or_else(|| record.energy.as_deref().and_then(energy_type)),
+                artist: record.artist,
+                name_chinese: record.name_chinese,
+                name_en: record.name_en,
+                last_fetched_at: record.last_fetched_at.into(),
             })
         })
         .boxed()
     }
-    pub async fn upsert_one_piece(&self, card: OnePieceCard) {
+    pub async fn upsert_one_piece(&self, card: OnePieceCard) -> Result<(), RepositoryError> {
         sqlx::query!(
             "
-            INSERT INTO one_piece(code, name, img_src, rarity, set_name, type, get_info)
-            VALUES($1, $2, $3, $4, $5, $6, $7)",
+            INSERT INTO one_piece(
+                code, name, img_src, rarity, set_name, type, get_info, language, rarity_raw, type_raw)
+            VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT(code, language)
+            DO UPDATE
+            SET name = $2, img_src = $3, rarity = $4, set_name = $5, type = $6, get_info = $7,
+                rarity_raw = $9, type_raw = $10, last_fetched_at = NOW()",
             card.code,
             card.name,
             card.img_src,
-            card.rarity as OnePieceCardRarity,
+            &card.rarity as &OnePieceCardRarity,
             card.set_name,
-            card.r#type as OnePieceCardType,
+            &card.r#type as &OnePieceCardType,
             card.get_info,
+            card.language.to_string(),
+            card.rarity.raw(),
+            card.r#type.raw(),
         )
         .execute(&self.pool)
-        .await
-        .unwrap();
+        .await?;
+        Ok(())
     }
-    pub fn list_one_piece(&self) -> BoxStream<Result<OnePieceCard, RepositoryError>> {
+    pub fn list_one_piece(&self) -> BoxStream<'_, Result<OnePieceCard, RepositoryError>> {
         sqlx::query_as!(
             OnePieceCardDto,
             r#"
-            SELECT code, name, img_src, rarity AS "rarity!: _", set_name, type AS "type!: _", get_info
-            FROM one_piece
+            SELECT
+                op.code, op.name, op.img_src, op.rarity AS "rarity!: _", op.set_name,
+                op.type AS "type!: _", op.get_info, op.language, op.rarity_raw, op.type_raw,
+                counterpart.name AS "counterpart_name?",
+                op.last_fetched_at AS "last_fetched_at: OffsetDateTime"
+            FROM one_piece op
+            LEFT JOIN one_piece counterpart
+                ON counterpart.code = op.code AND counterpart.language <> op.language
             "#,
         )
         .fetch(&self.pool)
-        .map_ok(|c|c.into())
-        .map_err(|e|e.into())
+        .map(|c| OnePieceCard::try_from(c?))
         .boxed()
     }
-    pub async fn get_ws_progress(&self) -> Result<i32, RepositoryError> {
+    pub async fn get_ws_progress(&self, region: WsRegion) -> Result<i32, RepositoryError> {
         let record = sqlx::query!(
-            "SELECT current_page FROM ws_progress WHERE id = (SELECT id FROM ws_progress_id_seq)"
+            "SELECT current_page FROM ws_progress WHERE region = $1",
+            region.to_string()
         )
         .fetch_one(&self.pool)
         .await?;
         Ok(record.current_page)
     }
-    pub async fn update_ws_progress(&self, current_page: i32) -> Result<(), RepositoryError> {
-        sqlx::query!("UPDATE ws_progress SET current_page = $1 WHERE id = (SELECT id FROM ws_progress_id_seq)", current_page)
-            .execute(&self.pool)
-            .await?;
+    pub async fn update_ws_progress(
+        &self,
+        region: WsRegion,
+        current_page: i32,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query!(
+            "UPDATE ws_progress SET current_page = $2 WHERE region = $1",
+            region.to_string(),
+            current_page
+        )
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
     pub async fn save_ws_cards(&self, cards: Vec<WsCard>) -> Result<(), RepositoryError> {
         let unzipped = cards.into_iter().fold(
-            (vec![], vec![], vec![], vec![], vec![], vec![]),
+            (
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+            ),
             |mut acc, card| {
                 acc.0.push(card.code);
                 acc.1.push(card.name);
@@ -476,36 +1763,324 @@ impl Repository {
                 acc.3.push(card.img_src);
                 acc.4.push(card.rarity.unwrap_or("UNKNOWN".to_string()));
                 acc.5.push(card.set_name);
+                acc.6.push(card.language.to_string());
+                acc.7.push(card.base_code);
                 acc
             },
         );
         sqlx::query!(
             "
-            INSERT INTO ws_cards(code, name, set_code, img_src, rarity, set_name)
+            INSERT INTO ws_cards(code, name, set_code, img_src, rarity, set_name, language, base_code)
             SELECT *
-            FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[], $4::TEXT[], $5::TEXT[], $6::TEXT[])
+            FROM UNNEST(
+                $1::TEXT[], $2::TEXT[], $3::TEXT[], $4::TEXT[],
+                $5::TEXT[], $6::TEXT[], $7::TEXT[], $8::TEXT[]
+            )
+            ON CONFLICT(code)
+            DO UPDATE
+            SET name = EXCLUDED.name, set_code = EXCLUDED.set_code, img_src = EXCLUDED.img_src,
+                rarity = EXCLUDED.rarity, set_name = EXCLUDED.set_name,
+                language = EXCLUDED.language, base_code = EXCLUDED.base_code,
+                last_fetched_at = NOW()
             ",
             &unzipped.0,
             &unzipped.1,
             &unzipped.2,
             &unzipped.3,
             &unzipped.4,
-            &unzipped.5
+            &unzipped.5,
+            &unzipped.6,
+            &unzipped.7
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+    pub async fn upsert_ws_sets(
+        &self,
+        region: WsRegion,
+        expansions: &[WsExpansion],
+    ) -> Result<(), RepositoryError> {
+        for (position, exp) in expansions.iter().enumerate() {
+            sqlx::query!(
+                "INSERT INTO ws_sets(region, expansion_id, name, position) VALUES($1, $2, $3, $4)
+                ON CONFLICT(region, expansion_id)
+                DO UPDATE SET name = $3, position = $4",
+                region.to_string(),
+                exp.id,
+                exp.name,
+                position as i32
+            )
+            .execute(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+    /// The newest `latest` sets plus every set that never finished syncing.
+    pub async fn get_ws_sets_to_sync(
+        &self,
+        region: WsRegion,
+        latest: i64,
+    ) -> Result<Vec<WsExpansion>, RepositoryError> {
+        let sets = sqlx::query_as!(
+            WsExpansion,
+            "SELECT expansion_id AS id, name FROM ws_sets
+            WHERE region = $1
+            AND (
+                completed_at IS NULL
+                OR expansion_id IN (
+                    SELECT expansion_id FROM ws_sets WHERE region = $1 ORDER BY position LIMIT $2
+                )
+            )
+            ORDER BY position",
+            region.to_string(),
+            latest
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(sets)
+    }
+    pub async fn complete_ws_set(
+        &self,
+        region: WsRegion,
+        expansion_id: &str,
+        card_count: i32,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query!(
+            "UPDATE ws_sets SET completed_at = NOW(), card_count = $3
+            WHERE region = $1 AND expansion_id = $2",
+            region.to_string(),
+            expansion_id,
+            card_count
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+    pub async fn enqueue_ws_card_details(&self) -> Result<(), RepositoryError> {
+        sqlx::query!(
+            "INSERT INTO ws_card_detail_fetchable(code)
+            SELECT code FROM ws_cards
+            ON CONFLICT(code) DO NOTHING"
         )
         .execute(&self.pool)
         .await?;
         Ok(())
     }
-    pub fn get_ws_cards(&self) -> BoxStream<Result<WsCard, RepositoryError>> {
+    pub fn get_ws_detail_fetchable(
+        &self,
+        language: Language,
+    ) -> BoxStream<'_, Result<String, RepositoryError>> {
+        sqlx::query!(
+            "SELECT fetchable.code
+            FROM ws_card_detail_fetchable fetchable
+            JOIN ws_cards card ON card.code = fetchable.code
+            WHERE fetchable.fetched = false AND card.language = $1",
+            language.to_string()
+        )
+        .fetch(&self.pool)
+        .map_ok(|r| r.code)
+        .map_err(RepositoryError::from)
+        .boxed()
+    }
+    pub async fn save_ws_card_detail(&self, detail: WsCardDetail) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            "
+            INSERT INTO ws_card_details(
+                code, side, color, card_type, level, cost, power, soul, triggers, traits, text)
+            VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT(code)
+            DO UPDATE
+            SET side = $2, color = $3, card_type = $4, level = $5, cost = $6, power = $7,
+                soul = $8, triggers = $9, traits = $10, text = $11
+            ",
+            detail.code,
+            detail.side,
+            detail.color,
+            detail.card_type,
+            detail.level,
+            detail.cost,
+            detail.power,
+            detail.soul,
+            &detail.triggers,
+            &detail.traits,
+            detail.text,
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "UPDATE ws_card_detail_fetchable SET fetched = true WHERE code = $1",
+            detail.code
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+    pub fn get_ws_cards(&self) -> BoxStream<'_, Result<WsCard, RepositoryError>> {
         sqlx::query_as!(
             WsCardDto,
-            "SELECT code, name, set_code, img_src, rarity, set_name FROM ws_cards"
+            r#"
+            SELECT
+                ws.code, ws.name, ws.set_code, ws.img_src, ws.rarity, ws.set_name,
+                ws.language, ws.base_code, counterpart.name AS "counterpart_name?",
+                ws.last_fetched_at AS "last_fetched_at: OffsetDateTime"
+            FROM ws_cards ws
+            LEFT JOIN ws_cards counterpart
+                ON counterpart.base_code = ws.base_code AND counterpart.language <> ws.language
+            "#
         )
         .fetch(&self.pool)
         .map_ok(|dto| dto.into())
         .map_err(RepositoryError::from)
         .boxed()
     }
+    /// Printings of every game in the set `set_ref`: Pokémon by expansion code
+    /// (directly or through its JP code), One Piece by code prefix and WS by
+    /// set code.
+    ///
+    /// Pokémon candidates are JP printings from tcgcollector, since titles
+    /// name JP cards in Japanese. tcgcollector only has English names, so each
+    /// printing is named by the bigweb products of the same set and number,
+    /// one candidate per distinct name, with the English name as `alt_name`.
+    /// Printings bigweb does not sell only carry their English name. Asia
+    /// printings are left out, as their names are Chinese or Thai.
+    pub async fn get_listing_candidates(
+        &self,
+        set_ref: &str,
+    ) -> Result<Vec<ListingCandidate>, RepositoryError> {
+        let candidates = sqlx::query_as!(
+            ListingCandidate,
+            r#"
+            SELECT 'ptcg' AS "game!", tc.url AS "id!", COALESCE(jp.name, tc.name) AS "name!",
+                CASE WHEN jp.name IS NULL THEN NULL ELSE tc.name END AS alt_name,
+                tc.rarity::TEXT AS rarity
+            FROM tcg_collector tc
+            LEFT JOIN LATERAL (
+                SELECT DISTINCT cp.name FROM card_prices cp
+                WHERE cp.game = 'ptcg' AND LOWER(cp.set_code) = LOWER(tc.exp_code)
+                    AND LTRIM(SPLIT_PART(cp.number, '/', 1), '0')
+                        = LTRIM(SPLIT_PART(tc.number, '/', 1), '0')
+            ) jp ON true
+            WHERE LOWER(tc.exp_code) = LOWER($1)
+            UNION ALL
+            SELECT 'one_piece', language || ':' || code, name, NULL, rarity::TEXT
+            FROM one_piece
+            WHERE LOWER(SPLIT_PART(code, '-', 1)) = LOWER($1)
+            UNION ALL
+            SELECT 'ws', code, name, NULL, rarity
+            FROM ws_cards
+            WHERE LOWER(set_code) = LOWER($1)
+            "#,
+            set_ref
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(candidates)
+    }
+    pub async fn get_card_revisions(
+        &self,
+        since: NaiveDate,
+    ) -> Result<Vec<CardRevision>, RepositoryError> {
+        let revisions = sqlx::query_as!(
+            CardRevision,
+            r#"
+            SELECT source, card_key, field, old_value, new_value, changed_at::TEXT AS "changed_at!"
+            FROM card_revisions
+            WHERE changed_at >= $1::DATE
+            ORDER BY changed_at, id
+            "#,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(revisions)
+    }
+}
+
+struct TcgCollectorAttack {
+    card_url: String,
+    name: String,
+    cost: Vec<String>,
+    damage: Option<String>,
+    effect: Option<String>,
+}
+
+/// Replaces the card's attacks, keeping their order.
+async fn write_tcg_collector_attacks(
+    conn: &mut PgConnection,
+    card_url: &str,
+    attacks: &[PtcgAttack],
+) -> Result<(), RepositoryError> {
+    sqlx::query!(
+        "DELETE FROM tcg_collector_attack WHERE card_url = $1",
+        card_url
+    )
+    .execute(&mut *conn)
+    .await?;
+    for (position, attack) in attacks.iter().enumerate() {
+        sqlx::query!(
+            "
+            INSERT INTO tcg_collector_attack(card_url, position, name, cost, damage, effect)
+            VALUES($1, $2, $3, $4, $5, $6)
+            ",
+            card_url,
+            position as i32,
+            attack.name,
+            &attack.cost,
+            attack.damage,
+            attack.effect,
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+struct FieldChange {
+    field: &'static str,
+    old: Option<String>,
+    new: Option<String>,
+}
+
+/// `Some` when an upsert is about to overwrite `old` with a different value.
+fn changed(field: &'static str, old: Option<String>, new: Option<&str>) -> Option<FieldChange> {
+    (old.as_deref() != new).then(|| FieldChange {
+        field,
+        old,
+        new: new.map(str::to_string),
+    })
+}
+
+async fn record_revisions<const N: usize>(
+    conn: &mut PgConnection,
+    source: &str,
+    card_key: &str,
+    changes: [Option<FieldChange>; N],
+) -> Result<(), RepositoryError> {
+    let (fields, (olds, news)): (Vec<_>, (Vec<_>, Vec<_>)) = changes
+        .into_iter()
+        .flatten()
+        .map(|c| (c.field.to_string(), (c.old, c.new)))
+        .unzip();
+    if fields.is_empty() {
+        return Ok(());
+    }
+    sqlx::query!(
+        "
+        INSERT INTO card_revisions(source, card_key, field, old_value, new_value)
+        SELECT $1, $2, * FROM UNNEST($3::TEXT[], $4::TEXT[], $5::TEXT[])
+        ",
+        source,
+        card_key,
+        &fields,
+        &olds as &[Option<String>],
+        &news as &[Option<String>],
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -516,6 +2091,10 @@ pub struct WsCardDto {
     pub img_src: String,
     pub rarity: Option<String>,
     pub set_name: String,
+    pub language: String,
+    pub base_code: String,
+    pub counterpart_name: Option<String>,
+    pub last_fetched_at: OffsetDateTime,
 }
 
 impl From<WsCardDto> for WsCard {
@@ -527,7 +2106,10 @@ impl From<WsCardDto> for WsCard {
             img_src: value.img_src,
             rarity: value.rarity,
             set_name: value.set_name,
-            last_fetched_at: LastFetchedAt::default(),
+            language: value.language.parse().unwrap_or_default(),
+            base_code: value.base_code,
+            counterpart_name: value.counterpart_name,
+            last_fetched_at: value.last_fetched_at.into(),
         }
     }
 }
@@ -541,20 +2123,41 @@ pub struct OnePieceCardDto {
     pub set_name: String,
     pub r#type: OnePieceCardType,
     pub get_info: String,
+    pub language: String,
+    pub rarity_raw: Option<String>,
+    pub type_raw: Option<String>,
+    pub counterpart_name: Option<String>,
+    pub last_fetched_at: OffsetDateTime,
 }
 
-impl From<OnePieceCardDto> for OnePieceCard {
-    fn from(value: OnePieceCardDto) -> Self {
-        Self {
+impl TryFrom<OnePieceCardDto> for OnePieceCard {
+    type Error = RepositoryError;
+
+    fn try_from(value: OnePieceCardDto) -> Result<Self, Self::Error> {
+        let language = value
+            .language
+            .parse()
+            .map_err(|_| RepositoryError::UnknownLanguage(value.language.clone()))?;
+        let rarity = match (value.rarity, value.rarity_raw) {
+            (OnePieceCardRarity::Unknown(_), Some(raw)) => OnePieceCardRarity::Unknown(raw),
+            (rarity, _) => rarity,
+        };
+        let r#type = match (value.r#type, value.type_raw) {
+            (OnePieceCardType::Unknown(_), Some(raw)) => OnePieceCardType::Unknown(raw),
+            (r#type, _) => r#type,
+        };
+        Ok(Self {
             name: value.name,
             code: value.code,
             img_src: value.img_src,
-            rarity: value.rarity,
+            rarity,
             set_name: value.set_name,
-            r#type: value.r#type,
+            r#type,
             get_info: value.get_info,
-            last_fetched_at: LastFetchedAt::default(),
-        }
+            language,
+            counterpart_name: value.counterpart_name,
+            last_fetched_at: value.last_fetched_at.into(),
+        })
     }
 }
 
@@ -562,6 +2165,8 @@ impl From<OnePieceCardDto> for OnePieceCard {
 pub enum RepositoryError {
     #[error("backend error")]
     BackendError(#[from] sqlx::Error),
+    #[error("unknown language {0:?}")]
+    UnknownLanguage(String),
 }
 
 pub struct ExpansionLink<'a> {
@@ -595,3 +2200,393 @@ impl<'a> PrintingLink<'a> {
         self.conn.commit().await.unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    fn the_ptcg_card(name: &str) -> ThePTCGCard {
+        ThePTCGCard {
+            code: "10001".to_string(),
+            kind: "pokemon".to_string(),
+            evolve_marker: None,
+            name: name.to_string(),
+            img_src: None,
+            hp: Some("60".to_string()),
+            weak_point: None,
+            resist: None,
+            escape: None,
+            expansion_symbol: None,
+            energy: None,
+            number: Some("001/071".to_string()),
+            artist: "Ken Sugimori".to_string(),
+            set_code: Some("sv5K".to_string()),
+            attacks: vec![],
+            abilities: vec![],
+            rules: vec![],
+        }
+    }
+
+    // These run against a fresh database each:
+    // `DATABASE_URL=... cargo test -- --ignored`.
+
+    #[sqlx::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn empty_live_codes_flag_nothing(pool: PgPool) {
+        let repository = Repository { pool };
+        let live = vec!["1".to_string(), "2".to_string()];
+        let (new, _) = repository
+            .refresh_fetchable(PtcgRegion::Tw, &live, "sv5K")
+            .await
+            .unwrap();
+        assert_eq!(new, 2);
+        let (_, removed) = repository
+            .refresh_fetchable(PtcgRegion::Tw, &[], "sv5K")
+            .await
+            .unwrap();
+        assert_eq!(removed, 0);
+        let (_, removed) = repository
+            .refresh_fetchable(PtcgRegion::Tw, &live[..1], "sv5K")
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn renamed_printing_keeps_name_revision(pool: PgPool) {
+        let repository = Repository { pool: pool.clone() };
+        repository
+            .upsert_the_ptcg_card(PtcgRegion::Tw, &the_ptcg_card("皮卡丘"))
+            .await
+            .unwrap();
+        repository
+            .upsert_the_ptcg_card(PtcgRegion::Tw, &the_ptcg_card("雷丘"))
+            .await
+            .unwrap();
+
+        let name: String = sqlx::query_scalar(
+            "SELECT name FROM pokemon_trainer_printing WHERE region = 'tw' AND code = '10001'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(name, "雷丘");
+        let revisions = repository
+            .get_card_revisions(NaiveDate::from_ymd_opt(2000, 1, 1).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].card_key, "tw:10001");
+        assert_eq!(revisions[0].field, "name");
+        assert_eq!(revisions[0].old_value.as_deref(), Some("皮卡丘"));
+        assert_eq!(revisions[0].new_value.as_deref(), Some("雷丘"));
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn approved_en_name_keeps_existing_name(pool: PgPool) {
+        sqlx::raw_sql(
+            "
+            INSERT INTO ptcg_en_expansions(code, name_en, release_date)
+            VALUES('SSP', 'Surging Sparks', '2024-11-08');
+            INSERT INTO ptcg_en_cards(url, name, number, exp_code)
+            VALUES('en/1', 'Pikachu ex', '057/191', 'SSP');
+            INSERT INTO pokemon_trainer_printing(code, name, kind, number, expansion_code, name_en)
+            VALUES('1', '皮卡丘ex', 'pokemon', '057/191', 'sv8', NULL),
+                ('2', '皮卡丘ex', 'pokemon', '057/191', 'sv8a', 'Pikachu');
+            INSERT INTO ptcg_en_name_links(id, en_url, printing_code, score)
+            VALUES(1, 'en/1', '1', 0.6), (2, 'en/1', '2', 0.6);
+            ",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let repository = Repository { pool: pool.clone() };
+        assert_eq!(repository.approve_en_name_links(&[1, 2]).await.unwrap(), 1);
+        let names: Vec<(String, Option<String>)> =
+            sqlx::query_as("SELECT code, name_en FROM pokemon_trainer_printing ORDER BY code")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            names,
+            vec![
+                ("1".to_string(), Some("Pikachu ex".to_string())),
+                ("2".to_string(), Some("Pikachu".to_string())),
+            ]
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn extra_outside_tw_expansions_stays_pending(pool: PgPool) {
+        sqlx::raw_sql(
+            "
+            INSERT INTO pokemon_trainer_expansion(id, series, release_date, code, name)
+            VALUES(gen_random_uuid(), 'SV', '2024-01-26', 'sv5K', 'ワイルドフォース');
+            INSERT INTO pokemon_trainer_printing(code, name, kind, number, expansion_code)
+            VALUES('1', '皮卡丘', 'pokemon', '001/071', 'sv5K');
+            INSERT INTO ptcg_extra_candidates(
+                id, code, source_code, source_url, name_en, number, expansion_code, confidence)
+            VALUES(1, 'd|1|120/071|sv5K', '1', 'jp/1', 'Pikachu', '120/071', 'sv5K', 0.8),
+                (2, 'd|1|120/100|sv9', '1', 'jp/2', 'Pikachu', '120/100', 'sv9', 0.8);
+            ",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let repository = Repository { pool: pool.clone() };
+        assert_eq!(
+            repository.approve_extra_candidates(&[1, 2]).await.unwrap(),
+            vec![1]
+        );
+        let status: String =
+            sqlx::query_scalar("SELECT status FROM ptcg_extra_candidates WHERE id = 2")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, "pending");
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn sale_price_is_lowest_latest_undamaged(pool: PgPool) {
+        sqlx::raw_sql(
+            "
+            INSERT INTO pokemon_trainer_expansion(id, series, release_date, code, name)
+            VALUES(gen_random_uuid(), 'SV', '2024-01-26', 'sv5K', 'ワイルドフォース');
+            INSERT INTO pokemon_trainer_printing(code, name, kind, number, expansion_code)
+            VALUES('1', '皮卡丘', 'pokemon', '001/071', 'sv5K');
+            INSERT INTO card_prices(
+                game, set_code, number, name, source, card_key, price, currency, condition,
+                observed_at
+            )
+            VALUES('ptcg', 'SV5K', '001/071', 'ピカチュウ', 'bigweb', '1', 50, 'JPY', 'normal',
+                    NOW() - INTERVAL '1 day'),
+                ('ptcg', 'SV5K', '001/071', 'ピカチュウ', 'bigweb', '1', 80, 'JPY', 'normal', NOW()),
+                ('ptcg', 'SV5K', '001/071', 'ピカチュウ', 'bigweb', '2', 60, 'JPY', 'normal', NOW()),
+                ('ptcg', 'SV5K', '001/071', 'ピカチュウ', 'bigweb', '3', 10, 'JPY', 'damaged', NOW());
+            ",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let repository = Repository { pool };
+        let cards: Vec<PokemonCard> = repository
+            .get_all_pokemon_trainer_printing(PtcgRegion::Tw)
+            .collect()
+            .await;
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].sale_price, Some(60));
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn wiki_chinese_name_joins_by_expansion_and_number(pool: PgPool) {
+        sqlx::raw_sql(
+            "
+            INSERT INTO pokemon_trainer_expansion(id, series, release_date, code, name, region)
+            VALUES(gen_random_uuid(), 'SV', '2023-06-16', 'sv2aC', '寶可夢卡牌151', 'th');
+            INSERT INTO ptcg_jp_expansions(code, name_en, release_date)
+            VALUES('sv2a', 'Pokémon Card 151', '2023-06-16');
+            INSERT INTO ptcg_expansion_map(region, jp_code, tw_code)
+            VALUES('th', 'sv2a', 'sv2aC');
+            INSERT INTO pokemon_trainer_printing(code, name, kind, number, expansion_code, region)
+            VALUES('1', 'มิวทู', 'pokemon', '150/165', 'sv2aC', 'th');
+            INSERT INTO pokewiki(number, name, rarity, exp_code, name_chinese)
+            VALUES('150', '超梦', 'U', 'SV2a', '超梦');
+            ",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let repository = Repository { pool };
+        let cards: Vec<PokemonCard> = repository
+            .get_all_pokemon_trainer_printing(PtcgRegion::Th)
+            .collect()
+            .await;
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].name_chinese.as_deref(), Some("超梦"));
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn one_piece_unknown_language_is_an_error(pool: PgPool) {
+        sqlx::raw_sql(
+            "
+            INSERT INTO one_piece(code, name, img_src, rarity, set_name, type, get_info, language)
+            VALUES('OP01-001', 'Roronoa Zoro', 'zoro.png', 'L', 'ROMANCE DAWN', 'Leader', '', 'fr');
+            ",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let repository = Repository { pool };
+        let cards: Vec<_> = repository.list_one_piece().collect().await;
+        assert!(matches!(
+            cards.as_slice(),
+            [Err(RepositoryError::UnknownLanguage(l))] if l == "fr"
+        ));
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn suggested_en_code_keeps_manual_ones(pool: PgPool) {
+        sqlx::raw_sql(
+            "
+            INSERT INTO pokemon_trainer_expansion(id, series, release_date, code, name, region)
+            VALUES(gen_random_uuid(), 'SV', '2023-06-16', 'sv2aC', '寶可夢卡牌151', 'tw'),
+                (gen_random_uuid(), 'SV', '2024-01-26', 'sv5KC', '狂野之力', 'tw');
+            INSERT INTO ptcg_jp_expansions(code, name_en, release_date)
+            VALUES('sv2a', 'Pokémon Card 151', '2023-06-16'),
+                ('sv5k', 'Wild Force', '2024-01-26');
+            INSERT INTO ptcg_expansion_map(region, jp_code, tw_code, en_code, source)
+            VALUES('tw', 'sv2a', 'sv2aC', 'MEW', 'manual'), ('tw', 'sv5k', 'sv5KC', NULL, 'auto');
+            ",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let repository = Repository { pool };
+        repository
+            .suggest_en_code("sv2a", "PAF", 0.6)
+            .await
+            .unwrap();
+        repository
+            .suggest_en_code("sv5k", "TEF", 0.9)
+            .await
+            .unwrap();
+        let mut map: Vec<_> = repository
+            .get_expansion_map(PtcgRegion::Tw)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.jp_code, m.en_code, m.en_score))
+            .collect();
+        map.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            map,
+            vec![
+                ("sv2a".to_string(), Some("MEW".to_string()), None),
+                ("sv5k".to_string(), Some("TEF".to_string()), Some(0.9)),
+            ]
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn listing_candidates_take_bigweb_japanese_names(pool: PgPool) {
+        sqlx::raw_sql(
+            "
+            INSERT INTO tcg_collector(name, number, exp_code, url)
+            VALUES('Pikachu', '025/165', 'SV2a', 'tc/1'), ('Mew', '151/165', 'SV2a', 'tc/2');
+            INSERT INTO card_prices(game, set_code, number, name, source, card_key, price, currency)
+            VALUES('ptcg', 'SV2A', '025/165', 'ピカチュウ', 'bigweb', '1', 30, 'JPY'),
+                ('ptcg', 'SV2A', '025/165', 'ピカチュウ(マスターボールミラー)', 'bigweb', '2', 900, 'JPY');
+            ",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let repository = Repository { pool };
+        let mut names: Vec<(String, String, Option<String>)> = repository
+            .get_listing_candidates("sv2a")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.id, c.name, c.alt_name))
+            .collect();
+        names.sort();
+        let pikachu = |name: &str| {
+            (
+                "tc/1".to_string(),
+                name.to_string(),
+                Some("Pikachu".to_string()),
+            )
+        };
+        assert_eq!(
+            names,
+            vec![
+                pikachu("ピカチュウ"),
+                pikachu("ピカチュウ(マスターボールミラー)"),
+                ("tc/2".to_string(), "Mew".to_string(), None),
+            ]
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn stored_page_backfills_detail(pool: PgPool) {
+        let repository = Repository { pool: pool.clone() };
+        let url = "https://www.tcgcollector.com/cards/1/pikachu";
+        sqlx::query("INSERT INTO tcg_collector(name, number, exp_code, url, html) VALUES('Pikachu', '001/071', 'SV5K', $1, '<html></html>')")
+            .bind(url)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let pages = repository.get_unparsed_tcg_collector().await.unwrap();
+        assert_eq!(pages, vec![(url.to_string(), "<html></html>".to_string())]);
+
+        let detail = PtcgJpCard {
+            desc: Some("It stores electricity.".to_string()),
+            hp: Some("60".to_string()),
+            attacks: vec![PtcgAttack {
+                name: "Thunder Shock".to_string(),
+                cost: vec!["Lightning".to_string()],
+                damage: Some("20".to_string()),
+                effect: None,
+            }],
+            ..Default::default()
+        };
+        repository
+            .save_tcg_collector_detail(url, &detail)
+            .await
+            .unwrap();
+
+        assert!(repository
+            .get_unparsed_tcg_collector()
+            .await
+            .unwrap()
+            .is_empty());
+        let cards = repository.get_ptcg_jp_cards().await.unwrap();
+        assert_eq!(cards[0].desc, detail.desc);
+        assert_eq!(cards[0].hp, detail.hp);
+        assert_eq!(cards[0].attacks, detail.attacks);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn yugioh_update_keeps_expansion_code(pool: PgPool) {
+        let repository = Repository { pool: pool.clone() };
+        let printing = |r#ref: &str| YugiohPrinting {
+            card_id: "4007".to_string(),
+            name_jp: "青眼の白龍".to_string(),
+            name_en: "Blue-Eyes White Dragon".to_string(),
+            rarity: "UR".to_string(),
+            number: "001".to_string(),
+            release_date: "2023-01-01".to_string(),
+            remark: String::new(),
+            expansion_name: "QUARTER CENTURY CHRONICLE side:UNITY".to_string(),
+            r#ref: r#ref.to_string(),
+            last_fetched_at: Default::default(),
+        };
+        repository
+            .upsert_yugioh_printing_detail(printing("QCCU-JP000"))
+            .await
+            .unwrap();
+        repository
+            .upsert_yugioh_printing_detail(printing("QCCU-JP001"))
+            .await
+            .unwrap();
+
+        let code: String = sqlx::query_scalar(
+            "SELECT expansion_code FROM yugioh_printing_detail WHERE card_id = '4007'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(code, "QCCU-JP001");
+    }
+}
@@ -0,0 +1,167 @@
+use serde::Deserialize;
+use tracing::info;
+
+use crate::error::Error;
+
+use super::get_source;
+
+const API_URL: &str = "https://api.bigweb.co.jp";
+/// bigweb's game id for the Pokémon card game.
+const POKEMON_GAME_ID: u32 = 9;
+
+pub struct BigwebScraper {}
+
+/// A bigweb card set, e.g. `SV5K`.
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct BigwebCardset {
+    pub id: i64,
+    pub name: String,
+    /// set code as printed on the cards, empty for supplies and bundles
+    #[serde(rename = "web", default)]
+    pub code: Option<String>,
+    #[serde(default)]
+    pub item_count: Option<i32>,
+}
+
+/// A product of a card set with its current sale price in yen.
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct BigwebProduct {
+    pub id: i64,
+    pub name: String,
+    /// collector number, e.g. `001/071`
+    #[serde(rename = "fname", default)]
+    pub number: Option<String>,
+    #[serde(default, deserialize_with = "rarity_slip")]
+    pub rarity: Option<String>,
+    pub price: Option<i64>,
+    /// condition or variant notes, e.g. `キズ有り`
+    #[serde(rename = "comment", default)]
+    pub remark: Option<String>,
+}
+
+/// Remark words bigweb uses for damaged copies.
+const DAMAGED_MARKS: [&str; 3] = ["キズ", "傷", "難あり"];
+
+impl BigwebProduct {
+    /// `damaged` when the remark flags the copy as such, `normal` otherwise.
+    pub fn condition(&self) -> &'static str {
+        let remark = self.remark.as_deref().unwrap_or_default();
+        if DAMAGED_MARKS.iter().any(|mark| remark.contains(mark)) {
+            "damaged"
+        } else {
+            "normal"
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Items<T> {
+    items: Vec<T>,
+    #[serde(default)]
+    pagenate: Option<Pagenate>,
+}
+
+#[derive(Deserialize)]
+struct Pagenate {
+    #[serde(rename = "pageCount")]
+    page_count: u32,
+}
+
+#[derive(Deserialize)]
+struct Rarity {
+    slip: Option<String>,
+}
+
+fn rarity_slip<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let rarity: Option<Rarity> = Option::deserialize(deserializer)?;
+    Ok(rarity.and_then(|r| r.slip).filter(|s| !s.is_empty()))
+}
+
+impl BigwebScraper {
+    pub fn new() -> Self {
+        Self {}
+    }
+    pub async fn fetch_cardsets(&self) -> Result<Vec<BigwebCardset>, Error> {
+        let url = format!("{}/cardsets?game_id={}", API_URL, POKEMON_GAME_ID);
+        let cardsets: Items<BigwebCardset> = serde_json::from_str(&get_source(&url).await?)?;
+        info!("found {} bigweb card sets", cardsets.items.len());
+        Ok(cardsets.items)
+    }
+    /// Every product of the card set, following the API's page count.
+    pub async fn fetch_products(&self, cardset_id: i64) -> Result<Vec<BigwebProduct>, Error> {
+        let mut products = vec![];
+        let mut page = 1;
+        loop {
+            let url = format!(
+                "{}/products?game_id={}&cardsets={}&page={}",
+                API_URL, POKEMON_GAME_ID, cardset_id, page
+            );
+            let parsed: Items<BigwebProduct> = serde_json::from_str(&get_source(&url).await?)?;
+            let page_count = parsed.pagenate.map_or(1, |p| p.page_count);
+            products.extend(parsed.items);
+            if page >= page_count {
+                break;
+            }
+            page += 1;
+        }
+        Ok(products)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_cardsets() {
+        let json = r#"{"items":[
+            {"id":3850,"name":"拡張パック「ワイルドフォース」","web":"SV5K","item_count":120},
+            {"id":12,"name":"サプライ","web":null}
+        ]}"#;
+        let cardsets: Items<BigwebCardset> = serde_json::from_str(json).unwrap();
+        assert_eq!(cardsets.items[0].code.as_deref(), Some("SV5K"));
+        assert_eq!(cardsets.items[0].item_count, Some(120));
+        assert_eq!(cardsets.items[1].code, None);
+    }
+
+    #[test]
+    fn parse_products_page() {
+        let json = r#"{"items":[
+            {"id":1,"name":"ピカチュウ","fname":"001/071","rarity":{"id":2,"slip":"C"},"price":30,"comment":""},
+            {"id":2,"name":"ナンジャモ","fname":"091/071","rarity":null,"price":null}
+        ],"pagenate":{"pageCount":3,"currentPage":1}}"#;
+        let products: Items<BigwebProduct> = serde_json::from_str(json).unwrap();
+        assert_eq!(products.pagenate.unwrap().page_count, 3);
+        assert_eq!(
+            products.items[0],
+            BigwebProduct {
+                id: 1,
+                name: "ピカチュウ".to_string(),
+                number: Some("001/071".to_string()),
+                rarity: Some("C".to_string()),
+                price: Some(30),
+                remark: Some(String::new()),
+            }
+        );
+        assert_eq!(products.items[1].rarity, None);
+        assert_eq!(products.items[1].price, None);
+    }
+
+    #[test]
+    fn damaged_condition_from_remark() {
+        let product = |remark: Option<&str>| BigwebProduct {
+            id: 1,
+            name: "ピカチュウ".to_string(),
+            number: None,
+            rarity: None,
+            price: Some(30),
+            remark: remark.map(str::to_string),
+        };
+        assert_eq!(product(None).condition(), "normal");
+        assert_eq!(product(Some("ミラー")).condition(), "normal");
+        assert_eq!(product(Some("【キズ有り】")).condition(), "damaged");
+    }
+}
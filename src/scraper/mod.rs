@@ -2,9 +2,11 @@ use scraper::ElementRef;
 
 use crate::error::Error;
 
+pub mod bigweb;
 pub mod one_piece;
 pub mod pokemon_wiki;
 pub mod ptcg;
+pub mod rarity;
 pub mod scraper_error;
 pub mod tcg_collector;
 pub mod ws;
@@ -1,22 +1,48 @@
 use std::path::Path;
 
 use scraper::Selector;
-use serde::Deserialize;
-use serde_json::json;
-use strum::AsRefStr;
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef},
+    Decode, Encode, Postgres,
+};
+use strum::EnumString;
 
 use crate::{
-    domain::LastFetchedAt,
-    error::{Error, ErrorCode},
+    domain::{Language, LastFetchedAt},
+    error::Error,
 };
-const BASEURL: &str = "https://www.onepiece-cardgame.com";
 
-pub(crate) struct OnePieceScraper {}
+#[derive(Debug, Clone, Copy, PartialEq, EnumString, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum OnePieceRegion {
+    Jp,
+    En,
+}
+
+impl OnePieceRegion {
+    fn base_url(&self) -> &'static str {
+        match self {
+            OnePieceRegion::Jp => "https://www.onepiece-cardgame.com",
+            OnePieceRegion::En => "https://en.onepiece-cardgame.com",
+        }
+    }
+    pub fn language(&self) -> Language {
+        match self {
+            OnePieceRegion::Jp => Language::Ja,
+            OnePieceRegion::En => Language::En,
+        }
+    }
+}
+
+pub(crate) struct OnePieceScraper {
+    pub region: OnePieceRegion,
+}
 impl OnePieceScraper {
     pub(crate) async fn set(&self) -> Result<Vec<String>, Error> {
         let mut results = vec![];
-        let series = "550105";
-        let url = format!("{}/cardlist/?series={}", BASEURL, series);
+        let url = format!("{}/cardlist/", self.region.base_url());
         let source = reqwest::Client::new()
             .get(&url)
             .send()
@@ -35,7 +61,7 @@ impl OnePieceScraper {
     }
     pub(crate) async fn products(&self) -> Result<Vec<OnePieceProduct>, Error> {
         let mut results = vec![];
-        let url = format!("{}/products", BASEURL);
+        let url = format!("{}/products", self.region.base_url());
         let source = reqwest::Client::new().get(url).send().await?.text().await?;
         let document = scraper::Html::parse_document(&source);
         let selector = &Selector::parse(".productsDetail").unwrap();
@@ -62,12 +88,9 @@ impl OnePieceScraper {
         }
         Ok(results)
     }
-    pub(crate) async fn scrape_cards(
-        &self,
-        series: &str,
-    ) -> Result<Vec<Result<OnePieceCard, ErrorCode>>, Error> {
+    pub(crate) async fn scrape_cards(&self, series: &str) -> Result<Vec<OnePieceCard>, Error> {
         let mut results = vec![];
-        let url = format!("{}/cardlist/?series={}", BASEURL, series);
+        let url = format!("{}/cardlist/?series={}", self.region.base_url(), series);
         let source = reqwest::Client::new()
             .get(&url)
             .send()
@@ -78,8 +101,7 @@ impl OnePieceScraper {
         let set_name_selector = &Selector::parse("#series option").unwrap();
         let set_name = document
             .select(set_name_selector)
-            .skip_while(|e| e.value().attr("selected").is_none())
-            .next()
+            .find(|e| e.value().attr("selected").is_some())
             .unwrap()
             .inner_html();
         let dls_selector = Selector::parse("div.resultCol dl").unwrap();
@@ -98,15 +120,9 @@ impl OnePieceScraper {
             let card_name = dl.select(card_name_selector).next().unwrap().inner_html();
             let code_selector = &Selector::parse("dt .infoCol span").unwrap();
             let rarity = dl.select(code_selector).nth(1).unwrap().inner_html();
-            let rarity = serde_json::from_value(json!(&rarity))?;
-            let card_type = dl
-                .select(code_selector)
-                .nth(2)
-                .unwrap()
-                .inner_html()
-                .trim()
-                .to_string();
-            let card_type: OnePieceCardType = serde_json::from_value(json!(&card_type))?;
+            let rarity = OnePieceCardRarity::from_label(rarity.trim());
+            let card_type = dl.select(code_selector).nth(2).unwrap().inner_html();
+            let card_type = OnePieceCardType::from_label(card_type.trim());
             let img_selector = &Selector::parse("dd img").unwrap();
             let img_src = dl
                 .select(img_selector)
@@ -116,7 +132,7 @@ impl OnePieceScraper {
                 .attr("src")
                 .unwrap();
             let path = Path::new(img_src);
-            let img_src = format!("{}{}", BASEURL, img_src.replace("..", ""));
+            let img_src = format!("{}{}", self.region.base_url(), img_src.replace("..", ""));
             let file_name = path.file_name().unwrap().to_string_lossy().to_string();
             let (code, _) = file_name.split_once('.').unwrap();
             let one_piece_card = OnePieceCard {
@@ -127,9 +143,11 @@ impl OnePieceScraper {
                 get_info: get_info.to_string(),
                 r#type: card_type,
                 set_name: set_name.clone(),
+                language: self.region.language(),
+                counterpart_name: None,
                 last_fetched_at: LastFetchedAt::default(),
             };
-            results.push(Ok(one_piece_card));
+            results.push(one_piece_card);
         }
         Ok(results)
     }
@@ -143,27 +161,95 @@ pub struct OnePieceCard {
     pub rarity: OnePieceCardRarity,
     pub set_name: String,
     pub r#type: OnePieceCardType,
+    pub language: Language,
+    /// Name of the same card code printed in the other language, if scraped.
+    pub counterpart_name: Option<String>,
     pub last_fetched_at: LastFetchedAt,
     pub get_info: String,
 }
 
-#[derive(Debug, Deserialize, sqlx::Type)]
-#[serde(rename_all = "UPPERCASE")]
-#[sqlx(type_name = "op_type_enum")]
+/// Card type as shown on the card list. Labels the scraper doesn't know yet are
+/// kept verbatim in `Unknown` so a new type never aborts a whole series.
+#[derive(Debug, Clone, PartialEq, EnumString, strum::Display)]
 pub enum OnePieceCardType {
+    #[strum(to_string = "LEADER", serialize = "リーダー")]
     Leader,
-    #[serde(alias = "事件")]
+    #[strum(to_string = "EVENT", serialize = "イベント", serialize = "事件")]
     Event,
-    #[serde(alias = "キャラ")]
+    #[strum(to_string = "CHARACTER", serialize = "キャラ")]
     Character,
+    #[strum(to_string = "STAGE", serialize = "ステージ")]
     Stage,
+    #[strum(to_string = "DON!!", serialize = "ドン!!")]
+    Don,
+    #[strum(default)]
+    Unknown(String),
+}
+
+impl OnePieceCardType {
+    fn db_label(&self) -> &'static str {
+        match self {
+            OnePieceCardType::Leader => "Leader",
+            OnePieceCardType::Event => "Event",
+            OnePieceCardType::Character => "Character",
+            OnePieceCardType::Stage => "Stage",
+            OnePieceCardType::Don => "Don",
+            OnePieceCardType::Unknown(_) => "Unknown",
+        }
+    }
+    fn from_db_label(label: &str) -> Self {
+        match label {
+            "Leader" => OnePieceCardType::Leader,
+            "Event" => OnePieceCardType::Event,
+            "Character" => OnePieceCardType::Character,
+            "Stage" => OnePieceCardType::Stage,
+            "Don" => OnePieceCardType::Don,
+            _ => OnePieceCardType::Unknown(String::new()),
+        }
+    }
+    pub fn from_label(label: &str) -> Self {
+        label
+            .parse()
+            .unwrap_or_else(|_| OnePieceCardType::Unknown(label.to_string()))
+    }
+    /// The scraped label when it didn't map to a known type.
+    pub fn raw(&self) -> Option<String> {
+        match self {
+            OnePieceCardType::Unknown(raw) => Some(raw.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl sqlx::Type<Postgres> for OnePieceCardType {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("op_type_enum")
+    }
+}
+
+impl Encode<'_, Postgres> for OnePieceCardType {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        <&str as Encode<Postgres>>::encode(self.db_label(), buf)
+    }
 }
 
-#[derive(Debug, Deserialize, sqlx::Type, AsRefStr)]
-#[serde(rename_all = "UPPERCASE")]
-#[sqlx(type_name = "op_rarity_enum")]
+impl<'r> Decode<'r, Postgres> for OnePieceCardType {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let label = <&str as Decode<Postgres>>::decode(value)?;
+        Ok(Self::from_db_label(label))
+    }
+}
+
+/// Card rarity as shown on the card list. See [`OnePieceCardType`] for how
+/// unrecognised labels are handled.
+#[derive(Debug, Clone, PartialEq, EnumString, strum::Display)]
 pub enum OnePieceCardRarity {
-    #[serde(alias = "SP卡", alias = "SPカード")]
+    #[strum(
+        to_string = "SP",
+        serialize = "SP卡",
+        serialize = "SPカード",
+        serialize = "SP CARD"
+    )]
     SP,
     R,
     #[allow(clippy::upper_case_acronyms)]
@@ -173,6 +259,67 @@ pub enum OnePieceCardRarity {
     UC,
     SR,
     L,
+    TR,
+    #[strum(to_string = "DON!!")]
+    Don,
+    #[strum(default)]
+    Unknown(String),
+}
+
+impl OnePieceCardRarity {
+    fn db_label(&self) -> &'static str {
+        match self {
+            OnePieceCardRarity::SP => "SP",
+            OnePieceCardRarity::R => "R",
+            OnePieceCardRarity::SEC => "SEC",
+            OnePieceCardRarity::C => "C",
+            OnePieceCardRarity::P => "P",
+            OnePieceCardRarity::UC => "UC",
+            OnePieceCardRarity::SR => "SR",
+            OnePieceCardRarity::L => "L",
+            OnePieceCardRarity::TR => "TR",
+            OnePieceCardRarity::Don => "DON",
+            OnePieceCardRarity::Unknown(_) => "Unknown",
+        }
+    }
+    fn from_db_label(label: &str) -> Self {
+        match label {
+            "DON" => OnePieceCardRarity::Don,
+            "Unknown" => OnePieceCardRarity::Unknown(String::new()),
+            label => Self::from_label(label),
+        }
+    }
+    pub fn from_label(label: &str) -> Self {
+        label
+            .parse()
+            .unwrap_or_else(|_| OnePieceCardRarity::Unknown(label.to_string()))
+    }
+    /// The scraped label when it didn't map to a known rarity.
+    pub fn raw(&self) -> Option<String> {
+        match self {
+            OnePieceCardRarity::Unknown(raw) => Some(raw.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl sqlx::Type<Postgres> for OnePieceCardRarity {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("op_rarity_enum")
+    }
+}
+
+impl Encode<'_, Postgres> for OnePieceCardRarity {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        <&str as Encode<Postgres>>::encode(self.db_label(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for OnePieceCardRarity {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let label = <&str as Decode<Postgres>>::decode(value)?;
+        Ok(Self::from_db_label(label))
+    }
 }
 
 #[derive(Debug)]
@@ -180,3 +327,47 @@ pub struct OnePieceProduct {
     pub title: String,
     pub date: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn rarity_aliases() {
+        assert_eq!(
+            OnePieceCardRarity::from_label("SPカード"),
+            OnePieceCardRarity::SP
+        );
+        assert_eq!(
+            OnePieceCardRarity::from_label("SP CARD"),
+            OnePieceCardRarity::SP
+        );
+        assert_eq!(OnePieceCardRarity::from_label("TR"), OnePieceCardRarity::TR);
+        assert_eq!(
+            OnePieceCardRarity::from_label("DON!!"),
+            OnePieceCardRarity::Don
+        );
+    }
+    #[test]
+    fn unknown_rarity_keeps_raw_label() {
+        let rarity = OnePieceCardRarity::from_label("SSP");
+        assert_eq!(rarity, OnePieceCardRarity::Unknown("SSP".to_string()));
+        assert_eq!(rarity.to_string(), "SSP");
+        assert_eq!(rarity.db_label(), "Unknown");
+    }
+    #[test]
+    fn type_aliases() {
+        assert_eq!(
+            OnePieceCardType::from_label("キャラ"),
+            OnePieceCardType::Character
+        );
+        assert_eq!(
+            OnePieceCardType::from_label("EVENT"),
+            OnePieceCardType::Event
+        );
+        assert_eq!(OnePieceCardType::from_label("DON!!"), OnePieceCardType::Don);
+        assert_eq!(
+            OnePieceCardType::from_label("BOSS"),
+            OnePieceCardType::Unknown("BOSS".to_string())
+        );
+    }
+}
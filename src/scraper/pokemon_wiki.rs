@@ -1,72 +1,333 @@
-use std::str::FromStr;
+use std::ops::Range;
 
-use scraper::Selector;
+use scraper::{ElementRef, Selector};
+use tracing::{info, warn};
 
 use crate::{
     domain::PtcgRarity,
     error::Error,
-    scraper::{get_source, Inner},
+    scraper::{
+        get_source,
+        rarity::{parse_poke_wiki_image, RaritySource},
+        Inner,
+    },
 };
 
+const WIKI_URL: &str = "https://wiki.52poke.com";
+/// Category listing every TCG expansion page on the wiki.
+const EXPANSION_CATEGORY_URL: &str = "https://wiki.52poke.com/wiki/Category:%E9%9B%86%E6%8D%A2%E5%BC%8F%E5%8D%A1%E7%89%8C%E6%89%A9%E5%85%85%E5%8C%85";
+
 #[derive(Clone)]
 pub struct PokemonWikiScraper {}
 
+#[derive(Debug, PartialEq)]
+pub struct WikiSetPage {
+    pub title: String,
+    pub url: String,
+    /// set code from the page's infobox, as printed, e.g. `SV5K`
+    pub code: Option<String>,
+}
+
 #[derive(Debug)]
 pub struct PokemonWikiCard {
     pub number: String,
     pub name: String,
     pub rarity: PtcgRarity,
     pub exp_code: String,
+    /// e.g. 宝可梦, 物品, 支援者
+    pub card_type: Option<String>,
+    pub link: Option<String>,
+    pub image_src: Option<String>,
+    /// name and effect text from the card's own wiki page
+    pub name_chinese: Option<String>,
+    pub text_chinese: Option<String>,
 }
 
 impl PokemonWikiScraper {
     pub fn new() -> Self {
         Self {}
     }
+    /// Cards of the expansion page, only those numbered within `range` when
+    /// one is given.
     pub async fn fetch_card_data_by_exp_url(
         &self,
         exp_url: &str,
         exp_code: &str,
+        range: Option<Range<i32>>,
     ) -> Result<Vec<PokemonWikiCard>, Error> {
         let source = get_source(exp_url).await?;
-        let document = scraper::Html::parse_document(&source);
-        let tr_selector =
-            &Selector::parse("table > tbody > tr:nth-child(2) > td > table > tbody > tr").unwrap();
-        let tr_selection = document.select(tr_selector);
-        let mut cards = vec![];
-        tr_selection.skip(1).for_each(|tr| {
-            let number_selector = &Selector::parse("td:nth-child(1)").unwrap();
-            let Some(number) = tr.select(number_selector).next() else {
-                return;
-            };
-            let number = number.inner_trim();
-            let name_selector = &Selector::parse("td:nth-child(2) a").unwrap();
-            let name = tr.select(name_selector).next().unwrap().inner_trim();
-
-            let rarity_selector_img =
-                &Selector::parse("td:nth-child(4) span.explain > img").unwrap();
-            let rarity = tr.select(rarity_selector_img).next();
-            let rarity = if let Some(_) = rarity {
-                PtcgRarity::A
-            } else {
-                let rarity_selector = &Selector::parse("td:nth-child(4) span.explain > b").unwrap();
-
-                let rarity = tr
-                    .select(rarity_selector)
-                    .next()
-                    .map(|elem| elem.inner_trim());
-
-                let rarity = rarity.unwrap_or("Unknown".to_string());
-                PtcgRarity::from_str(&rarity).unwrap_or(PtcgRarity::Unknown)
-            };
-            let card = PokemonWikiCard {
-                number,
-                name,
-                rarity,
-                exp_code: exp_code.to_string(),
+        let mut cards = parse_set_table(&source, exp_code);
+        if let Some(range) = range {
+            cards.retain(|card| number_in(&card.number, &range));
+        }
+        for card in cards.iter_mut() {
+            let Some(link) = &card.link else {
+                continue;
             };
-            cards.push(card);
-        });
+            let page = get_source(link).await?;
+            let (name_chinese, text_chinese) = parse_card_page(&page);
+            card.name_chinese = name_chinese;
+            card.text_chinese = text_chinese;
+        }
         Ok(cards)
     }
+    /// Every page of the expansion category, following its "next page" links,
+    /// with the set code read from each page.
+    pub async fn fetch_set_pages(&self) -> Result<Vec<WikiSetPage>, Error> {
+        let mut pages = vec![];
+        let mut category_url = Some(EXPANSION_CATEGORY_URL.to_string());
+        while let Some(url) = category_url {
+            let source = get_source(&url).await?;
+            let (links, next) = parse_category(&source);
+            pages.extend(links);
+            category_url = next;
+        }
+        info!("found {} wiki expansion pages", pages.len());
+        let mut set_pages = vec![];
+        for (title, url) in pages {
+            let source = get_source(&url).await?;
+            let code = parse_set_code(&source);
+            if code.is_none() {
+                warn!("no set code on {}", url);
+            }
+            set_pages.push(WikiSetPage { title, url, code });
+        }
+        Ok(set_pages)
+    }
+}
+
+/// Page titles and links, and the link to the next page of the category.
+fn parse_category(source: &str) -> (Vec<(String, String)>, Option<String>) {
+    let document = scraper::Html::parse_document(source);
+    let link_selector = &Selector::parse("#mw-pages .mw-category li a").unwrap();
+    let links = document
+        .select(link_selector)
+        .filter_map(|a| Some((text(a), format!("{}{}", WIKI_URL, a.attr("href")?))))
+        .collect();
+    let nav_selector = &Selector::parse("#mw-pages > a").unwrap();
+    let next = document
+        .select(nav_selector)
+        .find(|a| text(*a) == "下一页")
+        .and_then(|a| a.attr("href"))
+        .map(|href| format!("{}{}", WIKI_URL, href));
+    (links, next)
+}
+
+/// The value next to the infobox's 编号 heading.
+fn parse_set_code(source: &str) -> Option<String> {
+    let document = scraper::Html::parse_document(source);
+    let row_selector = &Selector::parse("table.roundy tr").unwrap();
+    let th_selector = &Selector::parse("th").unwrap();
+    let td_selector = &Selector::parse("td").unwrap();
+    document.select(row_selector).find_map(|tr| {
+        let th = tr.select(th_selector).next()?;
+        if !text(th).contains("编号") {
+            return None;
+        }
+        let code = text(tr.select(td_selector).next()?);
+        (!code.is_empty()).then_some(code)
+    })
+}
+
+fn parse_set_table(source: &str, exp_code: &str) -> Vec<PokemonWikiCard> {
+    let document = scraper::Html::parse_document(source);
+    let tr_selector =
+        &Selector::parse("table > tbody > tr:nth-child(2) > td > table > tbody > tr").unwrap();
+    let tr_selection = document.select(tr_selector);
+    let mut cards = vec![];
+    tr_selection.skip(1).for_each(|tr| {
+        let number_selector = &Selector::parse("td:nth-child(1)").unwrap();
+        let Some(number) = tr.select(number_selector).next() else {
+            return;
+        };
+        let number = number.inner_trim();
+        let name_selector = &Selector::parse("td:nth-child(2) a").unwrap();
+        let name_el = tr.select(name_selector).next().unwrap();
+        let name = name_el.inner_trim();
+        let link = name_el
+            .attr("href")
+            .map(|href| format!("{}{}", WIKI_URL, href));
+
+        let image_selector = &Selector::parse("td:nth-child(2) img").unwrap();
+        let image_src = tr
+            .select(image_selector)
+            .next()
+            .and_then(|img| img.attr("data-src").or_else(|| img.attr("src")))
+            .map(absolute_src);
+
+        let type_selector = &Selector::parse("td:nth-child(3)").unwrap();
+        let card_type = tr
+            .select(type_selector)
+            .next()
+            .map(text)
+            .filter(|t| !t.is_empty());
+
+        let card = PokemonWikiCard {
+            number,
+            name,
+            rarity: parse_rarity(tr),
+            exp_code: exp_code.to_string(),
+            card_type,
+            link,
+            image_src,
+            name_chinese: None,
+            text_chinese: None,
+        };
+        cards.push(card);
+    });
+    cards
+}
+
+/// Whether the collector number, e.g. `071/070`, falls within `range`.
+fn number_in(number: &str, range: &Range<i32>) -> bool {
+    let (n, _) = number.split_once('/').unwrap_or((number, ""));
+    n.trim().parse().is_ok_and(|n| range.contains(&n))
+}
+
+fn parse_rarity(tr: ElementRef) -> PtcgRarity {
+    let rarity_selector_img = &Selector::parse("td:nth-child(4) span.explain > img").unwrap();
+    if let Some(img) = tr.select(rarity_selector_img).next() {
+        let src = img.attr("src").unwrap_or_default();
+        return parse_poke_wiki_image(src).unwrap_or_else(|err| {
+            warn!("{err}");
+            PtcgRarity::Unknown
+        });
+    }
+    let rarity_selector = &Selector::parse("td:nth-child(4) span.explain > b").unwrap();
+    tr.select(rarity_selector)
+        .next()
+        .map(|elem| RaritySource::PokeWiki.parse_or_unknown(&elem.inner_trim()))
+        .unwrap_or(PtcgRarity::Unknown)
+}
+
+/// The page title without its set suffix, and the card's effect text.
+fn parse_card_page(source: &str) -> (Option<String>, Option<String>) {
+    let document = scraper::Html::parse_document(source);
+    let title_selector = &Selector::parse("#firstHeading").unwrap();
+    let name = document.select(title_selector).next().map(|title| {
+        let title = text(title);
+        match title.split_once('（') {
+            Some((name, _)) => name.trim().to_string(),
+            None => title,
+        }
+    });
+    let effect_selector = &Selector::parse(".mw-parser-output table.roundy td.effect").unwrap();
+    let effects: Vec<String> = document
+        .select(effect_selector)
+        .map(text)
+        .filter(|t| !t.is_empty())
+        .collect();
+    let effects = (!effects.is_empty()).then(|| effects.join("\n"));
+    (name, effects)
+}
+
+fn text(el: ElementRef) -> String {
+    el.text().collect::<String>().trim().to_string()
+}
+
+/// Image sources on the wiki are protocol-relative or site-relative.
+fn absolute_src(src: &str) -> String {
+    if let Some(rest) = src.strip_prefix("//") {
+        format!("https://{}", rest)
+    } else if src.starts_with('/') {
+        format!("{}{}", WIKI_URL, src)
+    } else {
+        src.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_set_row() {
+        let source = r#"
+<table><tbody>
+<tr><th>set</th></tr>
+<tr><td><table><tbody>
+  <tr><th>编号</th><th>名称</th><th>类别</th><th>稀有度</th></tr>
+  <tr>
+    <td>001/071</td>
+    <td><img src="//media.52poke.com/wiki/a.png"><a href="/wiki/%E7%9A%AE">皮卡丘</a></td>
+    <td>宝可梦</td>
+    <td><span class="explain"><img src="/wiki/images/20px-Rarity_Amazing.png"></span></td>
+  </tr>
+  <tr>
+    <td>002/071</td>
+    <td><a href="/wiki/b">雷丘</a></td>
+    <td>宝可梦</td>
+    <td><span class="explain"><b>UC</b></span></td>
+  </tr>
+</tbody></table></td></tr>
+</tbody></table>"#;
+        let cards = parse_set_table(source, "sv5k");
+        assert_eq!(cards.len(), 2);
+        assert_eq!(cards[0].rarity, PtcgRarity::A);
+        assert_eq!(cards[0].card_type.as_deref(), Some("宝可梦"));
+        assert_eq!(
+            cards[0].link.as_deref(),
+            Some("https://wiki.52poke.com/wiki/%E7%9A%AE")
+        );
+        assert_eq!(
+            cards[0].image_src.as_deref(),
+            Some("https://media.52poke.com/wiki/a.png")
+        );
+        assert_eq!(cards[1].rarity, PtcgRarity::U);
+        assert_eq!(cards[1].image_src, None);
+    }
+
+    #[test]
+    fn number_within_range() {
+        assert!(number_in("071/070", &(71..91)));
+        assert!(!number_in("091/070", &(71..91)));
+        assert!(!number_in("SV-P", &(71..91)));
+    }
+    #[test]
+    fn parse_category_links_and_next_page() {
+        let source = r#"
+<div id="mw-pages">
+  <a href="/index.php?title=Category:X&amp;pagefrom=B">下一页</a>
+  <div class="mw-category"><ul>
+    <li><a href="/wiki/%E5%8F%8C">双倍爆击（TCG）</a></li>
+  </ul></div>
+</div>"#;
+        let (links, next) = parse_category(source);
+        assert_eq!(
+            links,
+            vec![(
+                "双倍爆击（TCG）".to_string(),
+                "https://wiki.52poke.com/wiki/%E5%8F%8C".to_string()
+            )]
+        );
+        assert_eq!(
+            next.as_deref(),
+            Some("https://wiki.52poke.com/index.php?title=Category:X&pagefrom=B")
+        );
+        let (_, last) = parse_category(r#"<div id="mw-pages"><a href="/x">上一页</a></div>"#);
+        assert_eq!(last, None);
+    }
+
+    #[test]
+    fn parse_infobox_set_code() {
+        let source = r#"
+<table class="roundy"><tbody>
+  <tr><th>发售日期</th><td>2024年1月26日</td></tr>
+  <tr><th>系列编号</th><td> SV5K </td></tr>
+</tbody></table>"#;
+        assert_eq!(parse_set_code(source).as_deref(), Some("SV5K"));
+        assert_eq!(parse_set_code("<table class=\"roundy\"></table>"), None);
+    }
+
+    #[test]
+    fn parse_card_page_name_and_effect() {
+        let source = r#"
+<h1 id="firstHeading">皮卡丘（朱＆紫 151）</h1>
+<div class="mw-parser-output"><table class="roundy"><tbody>
+  <tr><td class="effect">在对手的下个回合，受到的伤害-20。</td></tr>
+</tbody></table></div>"#;
+        let (name, effect) = parse_card_page(source);
+        assert_eq!(name.as_deref(), Some("皮卡丘"));
+        assert_eq!(effect.as_deref(), Some("在对手的下个回合，受到的伤害-20。"));
+    }
 }
@@ -1,8 +1,13 @@
-use crate::{domain::PtcgRarity, error::Error};
+use crate::{
+    domain::{PtcgAbility, PtcgAttack},
+    error::Error,
+};
 use chrono::NaiveDate;
 use derive_builder::Builder;
 use html_escape::decode_html_entities;
 use scraper::{ElementRef, Selector};
+use serde::Deserialize;
+use strum::EnumString;
 
 use super::{get_source, scraper_error::ScraperError};
 
@@ -32,17 +37,53 @@ pub struct ThePTCGCard {
     pub number: Option<String>,
     pub artist: String,
     pub set_code: Option<String>,
+    pub attacks: Vec<PtcgAttack>,
+    pub abilities: Vec<PtcgAbility>,
+    /// Rule box text, e.g. the extra prize rule on Pokémon ex.
+    pub rules: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, EnumString, strum::Display, Deserialize)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PtcgRegion {
+    #[default]
+    Tw,
+    Hk,
+    Th,
+    Id,
+}
+
+impl PtcgRegion {
+    pub fn site_url(&self) -> String {
+        format!("{}/{}", POKEMON_TRAINER_SITE_URL_BASE, self)
+    }
+    pub fn detail_url(&self, code: &str) -> String {
+        format!("{}/card-search/detail/{}/", self.site_url(), code)
+    }
+    /// Language the region's cards are printed in, as exported.
+    pub fn language(&self) -> &'static str {
+        match self {
+            PtcgRegion::Tw => "zh-tw",
+            PtcgRegion::Hk => "zh-hk",
+            PtcgRegion::Th => "th",
+            PtcgRegion::Id => "id",
+        }
+    }
+    /// e.g. `https://asia.pokemon-card.com/tw/card-img/tw00012345.png`
+    pub fn image_url(&self, code: i32) -> String {
+        format!("{}/card-img/{}{:08}.png", self.site_url(), self, code)
+    }
 }
 
 #[derive(Clone)]
-pub struct PtcgScraper {}
+pub struct PtcgScraper {
+    pub region: PtcgRegion,
+}
 
 impl PtcgScraper {
-    pub fn new() -> Self {
-        Self {}
-    }
     pub async fn fetch_expansion(&self) -> Result<Vec<PtcgExpansion>, Error> {
-        let mut site_url = format!("{}/tw/card-search", POKEMON_TRAINER_SITE_URL_BASE);
+        let mut site_url = format!("{}/card-search", self.region.site_url());
         let mut exps = vec![];
         loop {
             let source = get_source(&site_url).await?;
@@ -94,8 +135,10 @@ impl PtcgScraper {
         Ok(exps)
     }
     pub async fn get_fetchables_by_exp(&self, exp_code: &str) -> Result<Vec<String>, Error> {
-        let mut exp_url =
-            format!("https://asia.pokemon-card.com/tw/card-search/list/?expansionCodes={exp_code}");
+        let mut exp_url = format!(
+            "{}/card-search/list/?expansionCodes={exp_code}",
+            self.region.site_url()
+        );
         let mut card_codes = vec![];
         loop {
             let source = get_source(&exp_url).await?;
@@ -113,7 +156,8 @@ impl PtcgScraper {
             match document.select(next_selector).next() {
                 Some(e) => {
                     exp_url = format!(
-                        "https://asia.pokemon-card.com{}",
+                        "{}{}",
+                        POKEMON_TRAINER_SITE_URL_BASE,
                         e.value().attr("href").unwrap()
                     )
                 }
@@ -122,8 +166,8 @@ impl PtcgScraper {
         }
         Ok(card_codes)
     }
-    pub async fn fetch_printing_detail(&self, card_url: &str) -> Result<ThePTCGCard, Error> {
-        let source = get_source(card_url).await?;
+    pub async fn fetch_printing_detail(&self, code: &str) -> Result<ThePTCGCard, Error> {
+        let source = get_source(&self.region.detail_url(code)).await?;
         let mut card_builder = ThePTCGCardBuilder::default();
         let document = scraper::Html::parse_document(&source);
         let common_header =
@@ -176,40 +220,38 @@ impl PtcgScraper {
         let artist = get_first_elem_inner_html(".illustrator a", document.root_element())
             .unwrap_or_default();
         card_builder.artist(artist);
-        let mut card_url = card_url.to_string();
-        card_url.pop();
-        let (_, code) = card_url.rsplit_once('/').unwrap();
+        let skills = parse_skills(document.root_element());
+        card_builder.attacks(skills.attacks);
+        card_builder.abilities(skills.abilities);
+        card_builder.rules(skills.rules);
         card_builder.code(code.to_string());
         card_builder.set_code(None);
         let card = card_builder.build().unwrap();
         Ok(card)
     }
-    pub async fn rarity_ids(&self, rarity: &PtcgRarity) -> Result<Vec<String>, Error> {
-        let rarity_label_number = match rarity {
-            PtcgRarity::C => 1,
-            PtcgRarity::U => 2,
-            PtcgRarity::R => 3,
-            PtcgRarity::RR => 4,
-            PtcgRarity::RRR => 5,
-            PtcgRarity::PR => 6,
-            PtcgRarity::TR => 7,
-            PtcgRarity::SR => 8,
-            PtcgRarity::HR => 9,
-            PtcgRarity::UR => 10,
-            PtcgRarity::Unknown => 11,
-            PtcgRarity::K => 12,
-            PtcgRarity::A => 13,
-            PtcgRarity::AR => 14,
-            PtcgRarity::SAR => 15,
-            PtcgRarity::S => 16,
-            PtcgRarity::SSR => 17,
-            PtcgRarity::ACE => 18,
-            _ => 0,
-        };
+    /// Card ids listed under a TW site rarity label, see [`super::rarity::RaritySource::TwSite`].
+    pub async fn rarity_ids(&self, label: &str) -> Result<Vec<String>, Error> {
+        self.search_ids(&PtcgSearchFilter::Rarity(label)).await
+    }
+    pub async fn regulation_ids(&self, mark: &str) -> Result<Vec<String>, Error> {
+        self.search_ids(&PtcgSearchFilter::Regulation(mark)).await
+    }
+    pub async fn energy_type_ids(&self, energy_id: &str) -> Result<Vec<String>, Error> {
+        self.search_ids(&PtcgSearchFilter::Energy(energy_id)).await
+    }
+    /// Values the site's card search form offers for `field`, e.g. the
+    /// regulation marks.
+    pub async fn search_form_values(&self, field: &str) -> Result<Vec<String>, Error> {
+        let url = format!("{}/card-search/", self.region.site_url());
+        let source = get_source(&url).await?;
+        Ok(parse_search_form_values(&source, field))
+    }
+    /// Walks every result page of a card search and collects the card ids.
+    async fn search_ids(&self, filter: &PtcgSearchFilter<'_>) -> Result<Vec<String>, Error> {
         let mut ids = vec![];
         let mut page_num = 1;
         loop {
-            let url = format!("https://asia.pokemon-card.com/tw/card-search/list/?pageNo={}&sortCondition=&keyword=&cardType=all&regulation=all&pokemonEnergy=&pokemonWeakness=&pokemonResistance=&pokemonMoveEnergy=&hpLowerLimit=none&hpUpperLimit=none&retreatCostLowerLimit=0&retreatCostUpperLimit=none&rarity%5B0%5D={}&illustratorName=&expansionCodes=", page_num, rarity_label_number);
+            let url = search_url(&self.region.site_url(), page_num, filter);
             let source = get_source(&url).await?;
             let document = scraper::Html::parse_document(&source);
             let selector = &Selector::parse("#noResult").unwrap();
@@ -233,9 +275,242 @@ impl PtcgScraper {
     }
 }
 
+/// Values of the search form's `field`, without `all`.
+fn parse_search_form_values(source: &str, field: &str) -> Vec<String> {
+    let document = scraper::Html::parse_document(source);
+    let selector = Selector::parse(&format!(
+        r#"select[name="{field}"] option, input[name="{field}"]"#
+    ))
+    .unwrap();
+    let mut values = vec![];
+    for option in document.select(&selector) {
+        let Some(value) = option.value().attr("value").map(str::trim) else {
+            continue;
+        };
+        if !value.is_empty() && value != "all" && !values.iter().any(|v| v == value) {
+            values.push(value.to_string());
+        }
+    }
+    values
+}
+
+/// `pokemonEnergy` ids of the card search, paired with the energy type name
+/// used by [`energy_type`]. Only Pokémon cards have one.
+pub const ENERGY_TYPES: [(&str, &str); 11] = [
+    ("1", "Grass"),
+    ("2", "Fire"),
+    ("3", "Water"),
+    ("4", "Lightning"),
+    ("5", "Psychic"),
+    ("6", "Fighting"),
+    ("7", "Darkness"),
+    ("8", "Metal"),
+    ("9", "Fairy"),
+    ("10", "Dragon"),
+    ("11", "Colorless"),
+];
+
+enum PtcgSearchFilter<'a> {
+    Rarity(&'a str),
+    Regulation(&'a str),
+    Energy(&'a str),
+}
+
+fn search_url(site_url: &str, page_num: i32, filter: &PtcgSearchFilter) -> String {
+    let (regulation, energy, rarity) = match filter {
+        PtcgSearchFilter::Rarity(label) => ("all", "", *label),
+        PtcgSearchFilter::Regulation(mark) => (*mark, "", ""),
+        PtcgSearchFilter::Energy(id) => ("all", *id, ""),
+    };
+    format!("{}/card-search/list/?pageNo={}&sortCondition=&keyword=&cardType=all&regulation={}&pokemonEnergy={}&pokemonWeakness=&pokemonResistance=&pokemonMoveEnergy=&hpLowerLimit=none&hpUpperLimit=none&retreatCostLowerLimit=0&retreatCostUpperLimit=none&rarity%5B0%5D={}&illustratorName=&expansionCodes=", site_url, page_num, regulation, energy, rarity)
+}
+
+/// Energy type name from an energy symbol image,
+/// e.g. `/tw/card-img/energy/Grass.png` -> `Grass`.
+pub fn energy_type(src: &str) -> Option<String> {
+    let file_name = src.rsplit('/').next()?;
+    let (name, _) = file_name.split_once('.')?;
+    Some(name.to_string())
+}
+
+const ABILITY_PREFIX: &str = "[特性]";
+
+#[derive(Debug, Default, PartialEq)]
+struct PtcgSkills {
+    attacks: Vec<PtcgAttack>,
+    abilities: Vec<PtcgAbility>,
+    rules: Vec<String>,
+}
+
+/// Splits the `.skill` blocks of a detail page into attacks, abilities
+/// (named `[特性]...`) and rule boxes (an effect without a name).
+fn parse_skills(root: ElementRef) -> PtcgSkills {
+    let skill_selector = Selector::parse(".skillInformation .skill").unwrap();
+    let cost_selector = Selector::parse(".skillCost img").unwrap();
+    let effect_selector = Selector::parse(".skillEffect").unwrap();
+    let mut skills = PtcgSkills::default();
+    for skill in root.select(&skill_selector) {
+        let name = get_first_elem_inner_html(".skillName", skill)
+            .map(|n| decode_html_entities(&n).to_string())
+            .filter(|n| !n.is_empty());
+        let effect = skill
+            .select(&effect_selector)
+            .next()
+            .map(|e| e.text().collect::<String>().trim().to_string())
+            .filter(|e| !e.is_empty());
+        match name {
+            Some(name) if name.starts_with(ABILITY_PREFIX) => {
+                skills.abilities.push(PtcgAbility {
+                    name: name.trim_start_matches(ABILITY_PREFIX).trim().to_string(),
+                    effect,
+                });
+            }
+            Some(name) => {
+                let cost = skill
+                    .select(&cost_selector)
+                    .filter_map(|img| img.value().attr("src"))
+                    .filter_map(energy_type)
+                    .collect();
+                let damage =
+                    get_first_elem_inner_html(".skillDamage", skill).filter(|d| !d.is_empty());
+                skills.attacks.push(PtcgAttack {
+                    name,
+                    cost,
+                    damage,
+                    effect,
+                });
+            }
+            None => skills.rules.extend(effect),
+        }
+    }
+    skills
+}
+
 fn get_first_elem_inner_html(s: &str, elem: ElementRef) -> Option<String> {
     let selector = &Selector::parse(s).unwrap();
     elem.select(selector)
         .next()
         .map(|s| s.inner_html().trim().to_owned())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse_skill_blocks() {
+        let source = r#"
+<div class="skillInformation">
+  <h3 class="commonHeader">招式</h3>
+  <div class="skill">
+    <span class="skillCost">
+      <img src="/tw/card-img/energy/Grass.png"><img src="/tw/card-img/energy/Colorless.png">
+    </span>
+    <span class="skillName">藤鞭</span>
+    <span class="skillDamage">30</span>
+    <p class="skillEffect"></p>
+  </div>
+  <div class="skill">
+    <span class="skillCost"><img src="/tw/card-img/energy/Grass.png"></span>
+    <span class="skillName">吸取</span>
+    <span class="skillDamage"></span>
+    <p class="skillEffect">回復自己10點HP。</p>
+  </div>
+</div>"#;
+        let document = scraper::Html::parse_document(source);
+        let skills = parse_skills(document.root_element());
+        assert_eq!(
+            skills.attacks,
+            vec![
+                PtcgAttack {
+                    name: "藤鞭".to_string(),
+                    cost: vec!["Grass".to_string(), "Colorless".to_string()],
+                    damage: Some("30".to_string()),
+                    effect: None,
+                },
+                PtcgAttack {
+                    name: "吸取".to_string(),
+                    cost: vec!["Grass".to_string()],
+                    damage: None,
+                    effect: Some("回復自己10點HP。".to_string()),
+                },
+            ]
+        );
+    }
+    #[test]
+    fn search_url_applies_one_filter() {
+        let site_url = PtcgRegion::Hk.site_url();
+        let url = search_url(&site_url, 2, &PtcgSearchFilter::Regulation("G"));
+        assert!(url.starts_with("https://asia.pokemon-card.com/hk/card-search/list/?pageNo=2&"));
+        assert!(url.contains("&regulation=G&pokemonEnergy=&"));
+        assert!(url.contains("&rarity%5B0%5D=&"));
+        let url = search_url(&site_url, 1, &PtcgSearchFilter::Energy("2"));
+        assert!(url.contains("&regulation=all&pokemonEnergy=2&"));
+        let url = search_url(&site_url, 1, &PtcgSearchFilter::Rarity("8"));
+        assert!(url.contains("&regulation=all&pokemonEnergy=&"));
+        assert!(url.contains("&rarity%5B0%5D=8&"));
+    }
+    #[test]
+    fn search_form_values_by_field() {
+        let source = r#"
+<form action="/tw/card-search/list/">
+  <select name="regulation">
+    <option value="all">全部</option>
+    <option value="F">F</option>
+    <option value="G">G</option>
+    <option value="H">H</option>
+  </select>
+  <select name="pokemonEnergy">
+    <option value="">全部</option>
+    <option value="1">草</option>
+    <option value="11">無</option>
+  </select>
+</form>"#;
+        assert_eq!(
+            parse_search_form_values(source, "regulation"),
+            vec!["F", "G", "H"]
+        );
+        assert_eq!(
+            parse_search_form_values(source, "pokemonEnergy"),
+            vec!["1", "11"]
+        );
+    }
+    #[test]
+    fn region_urls() {
+        assert_eq!(
+            PtcgRegion::Th.image_url(12345),
+            "https://asia.pokemon-card.com/th/card-img/th00012345.png"
+        );
+        assert_eq!(
+            PtcgRegion::Tw.detail_url("12345"),
+            "https://asia.pokemon-card.com/tw/card-search/detail/12345/"
+        );
+    }
+    #[test]
+    fn parse_ability_and_rule_blocks() {
+        let source = r#"
+<div class="skillInformation">
+  <div class="skill">
+    <span class="skillName">[特性]&nbsp;光合作用</span>
+    <p class="skillEffect">在自己的回合可以使用1次。</p>
+  </div>
+  <div class="skill">
+    <span class="skillName"></span>
+    <p class="skillEffect">寶可夢ex【昏厥】時，對手獲得2張獎賞卡。</p>
+  </div>
+</div>"#;
+        let document = scraper::Html::parse_document(source);
+        let skills = parse_skills(document.root_element());
+        assert!(skills.attacks.is_empty());
+        assert_eq!(
+            skills.abilities,
+            vec![PtcgAbility {
+                name: "光合作用".to_string(),
+                effect: Some("在自己的回合可以使用1次。".to_string()),
+            }]
+        );
+        assert_eq!(
+            skills.rules,
+            vec!["寶可夢ex【昏厥】時，對手獲得2張獎賞卡。".to_string()]
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use strum::IntoEnumIterator;
+use tracing::warn;
+
+use crate::domain::PtcgRarity;
+
+/// A site that spells `PtcgRarity` its own way.
+#[derive(strum::Display, Clone, Copy, Debug, PartialEq, strum::EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum RaritySource {
+    /// `rarity[0]` label ids of the asia.pokemon-card.com card search.
+    TwSite,
+    /// `title` of the rarity symbol on tcgcollector.com.
+    TcgCollector,
+    /// Rarity symbol text on the 52poke wiki.
+    PokeWiki,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+#[error("{site} has no rarity label {label:?}")]
+pub struct UnmappedRarity {
+    pub site: RaritySource,
+    pub label: String,
+}
+
+impl RaritySource {
+    /// The site's label for `rarity`, or `None` when the site has no such rarity.
+    pub fn label(&self, rarity: &PtcgRarity) -> Option<&'static str> {
+        match self {
+            RaritySource::TwSite => tw_site_label(rarity),
+            RaritySource::TcgCollector => tcg_collector_label(rarity),
+            RaritySource::PokeWiki => poke_wiki_label(rarity),
+        }
+    }
+    pub fn parse(&self, label: &str) -> Result<PtcgRarity, UnmappedRarity> {
+        let label = label.trim();
+        let label = match (self, label) {
+            (RaritySource::PokeWiki, "UC") => "U",
+            (_, label) => label,
+        };
+        PtcgRarity::iter()
+            .find(|r| self.label(r) == Some(label))
+            .ok_or_else(|| UnmappedRarity {
+                site: *self,
+                label: label.to_string(),
+            })
+    }
+    /// Like [`RaritySource::parse`], but logs unmapped labels and falls back to
+    /// `PtcgRarity::Unknown`.
+    pub fn parse_or_unknown(&self, label: &str) -> PtcgRarity {
+        self.parse(label).unwrap_or_else(|err| {
+            warn!("{err}");
+            PtcgRarity::Unknown
+        })
+    }
+}
+
+/// Rarities the wiki shows as a symbol image rather than text, decoded from the
+/// image file name, e.g. `.../20px-Rarity_Amazing.png`.
+pub fn parse_poke_wiki_image(src: &str) -> Result<PtcgRarity, UnmappedRarity> {
+    let file_name = src.rsplit('/').next().unwrap_or_default();
+    // thumbnails are prefixed with their width
+    let file_name = match file_name.split_once("px-") {
+        Some((width, name)) if width.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => file_name,
+    };
+    let stem = file_name
+        .rsplit_once('.')
+        .map_or(file_name, |(stem, _)| stem)
+        .to_lowercase();
+    let label = match stem.trim_start_matches("rarity_") {
+        "amazing" | "amazing_rare" => "A",
+        "radiant" | "radiant_rare" => "K",
+        "ace" | "ace_spec" => "ACE",
+        "shining" | "shiny" => "S",
+        "holo" | "rare_holo" => "H",
+        "promo" => "PR",
+        _ => {
+            return Err(UnmappedRarity {
+                site: RaritySource::PokeWiki,
+                label: file_name.to_string(),
+            })
+        }
+    };
+    RaritySource::PokeWiki.parse(label)
+}
+
+fn tw_site_label(rarity: &PtcgRarity) -> Option<&'static str> {
+    match rarity {
+        PtcgRarity::C => Some("1"),
+        PtcgRarity::U => Some("2"),
+        PtcgRarity::R => Some("3"),
+        PtcgRarity::RR => Some("4"),
+        PtcgRarity::RRR => Some("5"),
+        PtcgRarity::PR => Some("6"),
+        PtcgRarity::TR => Some("7"),
+        PtcgRarity::SR => Some("8"),
+        PtcgRarity::HR => Some("9"),
+        PtcgRarity::UR => Some("10"),
+        PtcgRarity::Unknown => Some("11"),
+        PtcgRarity::K => Some("12"),
+        PtcgRarity::A => Some("13"),
+        PtcgRarity::AR => Some("14"),
+        PtcgRarity::SAR => Some("15"),
+        PtcgRarity::S => Some("16"),
+        PtcgRarity::SSR => Some("17"),
+        PtcgRarity::ACE => Some("18"),
+        PtcgRarity::CSR | PtcgRarity::CHR | PtcgRarity::H | PtcgRarity::TD => None,
+    }
+}
+
+fn tcg_collector_label(rarity: &PtcgRarity) -> Option<&'static str> {
+    match rarity {
+        PtcgRarity::UR => Some("Ultra Rare (UR)"),
+        PtcgRarity::SSR => Some("Shiny Super Rare (SSR)"),
+        PtcgRarity::ACE => Some("ACE SPEC Rare (ACE)"),
+        PtcgRarity::HR => Some("Hyper Rare (HR)"),
+        PtcgRarity::SR => Some("Super Rare (SR)"),
+        PtcgRarity::SAR => Some("Special Art Rare (SAR)"),
+        PtcgRarity::CSR => Some("Character Super Rare (CSR)"),
+        PtcgRarity::AR => Some("Art Rare (AR)"),
+        PtcgRarity::CHR => Some("Character Rare (CHR)"),
+        PtcgRarity::S => Some("Shiny (S)"),
+        PtcgRarity::A => Some("Amazing Rare"),
+        PtcgRarity::H => Some("Rare Holo"),
+        PtcgRarity::K => Some("Radiant Rare (K)"),
+        PtcgRarity::PR => Some("Promo"),
+        PtcgRarity::RRR => Some("Triple Rare (RRR)"),
+        PtcgRarity::RR => Some("Double Rare (RR)"),
+        PtcgRarity::R => Some("Rare (R)"),
+        PtcgRarity::U => Some("Uncommon (U)"),
+        PtcgRarity::C => Some("Common (C)"),
+        PtcgRarity::TR => Some("Trainer Rare (TR)"),
+        PtcgRarity::TD | PtcgRarity::Unknown => None,
+    }
+}
+
+fn poke_wiki_label(rarity: &PtcgRarity) -> Option<&'static str> {
+    match rarity {
+        PtcgRarity::UR => Some("UR"),
+        PtcgRarity::SSR => Some("SSR"),
+        PtcgRarity::ACE => Some("ACE"),
+        PtcgRarity::HR => Some("HR"),
+        PtcgRarity::SR => Some("SR"),
+        PtcgRarity::SAR => Some("SAR"),
+        PtcgRarity::CSR => Some("CSR"),
+        PtcgRarity::AR => Some("AR"),
+        PtcgRarity::CHR => Some("CHR"),
+        PtcgRarity::S => Some("S"),
+        // shown as an image; see `parse_poke_wiki_image`
+        PtcgRarity::A => Some("A"),
+        PtcgRarity::H => Some("H"),
+        PtcgRarity::K => Some("K"),
+        PtcgRarity::PR => Some("PR"),
+        PtcgRarity::RRR => Some("RRR"),
+        PtcgRarity::RR => Some("RR"),
+        PtcgRarity::R => Some("R"),
+        PtcgRarity::U => Some("U"),
+        PtcgRarity::C => Some("C"),
+        PtcgRarity::TR => Some("TR"),
+        PtcgRarity::TD => Some("TD"),
+        PtcgRarity::Unknown => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unmapped(site: RaritySource) -> Vec<PtcgRarity> {
+        PtcgRarity::iter()
+            .filter(|r| site.label(r).is_none())
+            .collect()
+    }
+
+    #[test]
+    fn every_rarity_round_trips() {
+        for site in RaritySource::iter() {
+            for rarity in PtcgRarity::iter() {
+                if let Some(label) = site.label(&rarity) {
+                    assert_eq!(site.parse(label), Ok(rarity), "{site} {label}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unmapped_rarities_are_explicit() {
+        assert_eq!(
+            unmapped(RaritySource::TwSite),
+            vec![
+                PtcgRarity::CSR,
+                PtcgRarity::CHR,
+                PtcgRarity::H,
+                PtcgRarity::TD
+            ]
+        );
+        assert_eq!(
+            unmapped(RaritySource::TcgCollector),
+            vec![PtcgRarity::TD, PtcgRarity::Unknown]
+        );
+        assert_eq!(unmapped(RaritySource::PokeWiki), vec![PtcgRarity::Unknown]);
+    }
+
+    #[test]
+    fn wiki_rarity_images_decode_by_file_name() {
+        assert_eq!(
+            parse_poke_wiki_image("https://media.52poke.com/wiki/thumb/a/a1/Rarity_Amazing.png/20px-Rarity_Amazing.png"),
+            Ok(PtcgRarity::A)
+        );
+        assert_eq!(
+            parse_poke_wiki_image("/wiki/images/Rarity_Radiant.png"),
+            Ok(PtcgRarity::K)
+        );
+        assert_eq!(
+            parse_poke_wiki_image("Rarity_ACE_SPEC.png"),
+            Ok(PtcgRarity::ACE)
+        );
+    }
+
+    #[test]
+    fn unknown_labels_are_reported() {
+        assert_eq!(
+            RaritySource::TcgCollector.parse("Mega Rare"),
+            Err(UnmappedRarity {
+                site: RaritySource::TcgCollector,
+                label: "Mega Rare".to_string(),
+            })
+        );
+        assert_eq!(RaritySource::PokeWiki.parse("UC"), Ok(PtcgRarity::U));
+        assert_eq!(
+            parse_poke_wiki_image("Mega_Rarity.png"),
+            Err(UnmappedRarity {
+                site: RaritySource::PokeWiki,
+                label: "Mega_Rarity.png".to_string(),
+            })
+        );
+        assert_eq!(
+            RaritySource::PokeWiki.parse_or_unknown("??"),
+            PtcgRarity::Unknown
+        );
+    }
+}
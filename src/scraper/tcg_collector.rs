@@ -1,193 +1,257 @@
+use std::{collections::HashSet, path::PathBuf};
+
 use chrono::NaiveDate;
-use scraper::Selector;
+use scraper::{ElementRef, Html, Selector};
+use tracing::info;
 
-use crate::{domain::PtcgRarity, error::Error};
+use crate::{
+    domain::{PtcgAttack, PtcgRarity},
+    error::Error,
+};
 
-use super::{get_source, Inner};
+use super::{get_source, rarity::RaritySource, Inner};
 
-pub struct TcgCollectorScraper {}
+pub struct TcgCollectorScraper {
+    /// Keeps card detail pages on disk so re-runs do not fetch them again.
+    pub cache_dir: Option<PathBuf>,
+}
 
 impl TcgCollectorScraper {
-    pub async fn fetch_exps(&self) -> Result<Vec<PtcgJpExpansion>, Error> {
-        let url = "https://www.tcgcollector.com/expansions/jp?collectionProgressMode=anyCardVariant&releaseDateOrder=newToOld&displayAs=logos";
-        let source = get_source(url).await?;
-        let document = scraper::Html::parse_document(&source);
-
-        // example: Mar 22, 2024
-        let grid_items_sel = Selector::parse(".expansion-logo-grid-item").unwrap();
-        let grid_items = document.select(&grid_items_sel);
-        let mut exps = vec![];
-        for item in grid_items {
-            let sel = &Selector::parse(".expansion-logo-grid-item-release-date").unwrap();
-            let mut selected_release_date = item.select(sel);
-            let release_date = selected_release_date.next().unwrap().inner_trim();
-            let d = chrono::NaiveDate::parse_from_str(&release_date, "%b %d, %Y").unwrap();
-
-            let name_sel = &Selector::parse(".expansion-logo-grid-item-expansion-name").unwrap();
-            let name_el = item.select(name_sel).next().unwrap();
-            let name = name_el.inner_trim();
-
-            let code_sel = &Selector::parse(".expansion-logo-grid-item-expansion-code").unwrap();
-            let maybe_code = item
-                .select(code_sel)
-                .next()
-                .map(|e| e.inner_lowercase_trim());
-
-            let Some(code) = maybe_code else {
-                continue;
-            };
-            let link = name_el
-                .attr("href")
-                .map(|s| format!("https://www.tcgcollector.com{}", s));
-
-            let symbol_sel = &Selector::parse(".expansion-symbol").unwrap();
-            let symbol_src = item
-                .select(symbol_sel)
-                .next()
-                .map(|el| el.attr("src").unwrap().to_string());
-
-            let logo_sel = &Selector::parse(".expansion-logo-grid-item-expansion-logo").unwrap();
-            let logo_src = item
-                .select(logo_sel)
-                .next()
-                .map(|el| el.attr("src").unwrap().to_string());
-
-            let exp = PtcgJpExpansion {
-                name,
-                code,
-                link,
-                symbol_src,
-                logo_src,
-                release_date: d,
-            };
-            exps.push(exp);
-        }
-        Ok(exps)
+    pub async fn fetch_exps(
+        &self,
+        region: TcgCollectorRegion,
+        window: ReleaseWindow,
+    ) -> Result<Vec<TcgCollectorExpansion>, Error> {
+        let url = format!("https://www.tcgcollector.com/expansions/{}?collectionProgressMode=anyCardVariant&releaseDateOrder=newToOld&displayAs=logos", region);
+        let exps = fetch_pages(&url, parse_exps, |e| &e.code).await?;
+        Ok(exps
+            .into_iter()
+            .filter(|e| window.contains(e.release_date))
+            .collect())
     }
 
-    pub async fn fetch_card_detail_html(
-        &self,
-        link: &str,
-    ) -> Result<Vec<TcgCollectorCardDetail>, Error> {
+    /// Cards as the expansion's listing shows them, without their detail pages.
+    pub async fn fetch_card_list(&self, link: &str) -> Result<Vec<TcgCollectorCard>, Error> {
         let url = format!("{}?displayAs=list", link);
-        let source = get_source(&url).await?;
-        let document = scraper::Html::parse_document(&source);
-
-        let exp_code_sel = &Selector::parse("#card-search-result-title-expansion-code").unwrap();
-        let exp_code = document
-            .select(exp_code_sel)
-            .next()
-            .unwrap()
-            .inner_lowercase_trim();
-
-        let card_items_sel = &Selector::parse(".card-list-item").unwrap();
-        let card_items = document.select(card_items_sel);
-
-        let mut cards: Vec<TcgCollectorCardDetail> = vec![];
-        for item in card_items {
-            let name_sel = &Selector::parse(".card-list-item-entry-text").unwrap();
-            let url_path = item.select(name_sel).next().unwrap().attr("href").unwrap();
-            let url = format!("https://www.tcgcollector.com{}", url_path);
-            let html = get_source(&url).await?;
-
-            let name = item.select(name_sel).next().unwrap().inner_trim();
-
-            let number_sel = &Selector::parse(".card-list-item-card-number > span").unwrap();
-            let number = item.select(number_sel).next().unwrap().inner_trim();
-
-            let rarity_sel = &Selector::parse(".card-rarity-symbol").unwrap();
-            let rarity = item
-                .select(rarity_sel)
-                .next()
-                .map(|s| s.attr("title").unwrap_or_default())
-                .unwrap_or_default();
-            let rarity: PtcgRarity = TcgCollectorRarity(rarity).into();
+        fetch_pages(&url, parse_card_list, |c| &c.url).await
+    }
 
-            let card = TcgCollectorCardDetail {
-                name,
-                exp_code: exp_code.clone(),
-                number,
-                html,
-                rarity: Some(rarity),
-                url: format!("https://www.tcgcollector.com{}", url_path),
+    pub async fn fetch_cards(&self, link: &str) -> Result<Vec<PtcgJpCard>, Error> {
+        let items = self.fetch_card_list(link).await?;
+        let mut cards = vec![];
+        for item in items {
+            let html = self.get_detail_source(&item.url).await?;
+            let card = PtcgJpCard {
+                name: item.name,
+                number: item.number,
+                exp_code: item.exp_code,
+                url: item.url,
+                rarity: Some(item.rarity),
+                ..parse_card_detail(&html)
             };
             cards.push(card);
         }
-
         Ok(cards)
     }
+    /// Reads the detail page from the cache directory when there is one,
+    /// fetching and caching it otherwise.
+    async fn get_detail_source(&self, url: &str) -> Result<String, Error> {
+        let Some(cache_dir) = &self.cache_dir else {
+            return get_source(url).await;
+        };
+        let file_name = url
+            .trim_end_matches('/')
+            .rsplit('/')
+            .take(2)
+            .collect::<Vec<_>>()
+            .join("-");
+        let path = cache_dir.join(format!("{}.html", file_name));
+        if let Ok(html) = std::fs::read_to_string(&path) {
+            return Ok(html);
+        }
+        let html = get_source(url).await?;
+        std::fs::create_dir_all(cache_dir)?;
+        std::fs::write(&path, &html)?;
+        Ok(html)
+    }
     pub async fn fetch_card_rarity(
         &self,
         link: &str,
     ) -> Result<Vec<TcgCollectorCardRarity>, Error> {
-        let url = format!("{}?displayAs=list", link);
-        let source = get_source(&url).await?;
-        let document = scraper::Html::parse_document(&source);
+        let items = self.fetch_card_list(link).await?;
+        Ok(items
+            .into_iter()
+            .map(|c| TcgCollectorCardRarity {
+                rarity: c.rarity,
+                url: c.url,
+            })
+            .collect())
+    }
+}
 
-        let card_items_sel = &Selector::parse(".card-list-item").unwrap();
-        let card_items = document.select(card_items_sel);
+#[derive(strum::Display, strum::EnumString, Clone, Copy, Debug, PartialEq, Default)]
+#[strum(serialize_all = "lowercase")]
+pub enum TcgCollectorRegion {
+    #[default]
+    Jp,
+    /// English expansions, listed as "intl" on the site.
+    Intl,
+}
 
-        let mut cards: Vec<TcgCollectorCardRarity> = vec![];
-        for item in card_items {
-            let name_sel = &Selector::parse(".card-list-item-entry-text").unwrap();
-            let url_path = item.select(name_sel).next().unwrap().attr("href").unwrap();
-            let url = format!("https://www.tcgcollector.com{}", url_path);
+/// Limits listings to expansions released inside the window, bounds included.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReleaseWindow {
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+}
 
-            let rarity_sel = &Selector::parse(".card-rarity-symbol").unwrap();
-            let rarity = item
-                .select(rarity_sel)
-                .next()
-                .map(|s| s.attr("title").unwrap_or_default())
-                .unwrap_or_default();
-            let rarity: PtcgRarity = TcgCollectorRarity(rarity).into();
-            let card = TcgCollectorCardRarity { rarity, url };
-            cards.push(card);
-        }
+impl ReleaseWindow {
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.since.is_none_or(|since| date >= since) && self.until.is_none_or(|until| date <= until)
+    }
+}
 
-        Ok(cards)
+/// Follows `page=` until a page lists nothing new; the site repeats or empties
+/// pages past the end rather than failing.
+async fn fetch_pages<T>(
+    url: &str,
+    parse: fn(&Html) -> Vec<T>,
+    key: fn(&T) -> &str,
+) -> Result<Vec<T>, Error> {
+    let mut seen = HashSet::new();
+    let mut items = vec![];
+    let mut page = 1;
+    loop {
+        let source = get_source(&format!("{}&page={}", url, page)).await?;
+        let parsed = parse(&Html::parse_document(&source));
+        let before = items.len();
+        for item in parsed {
+            if seen.insert(key(&item).to_string()) {
+                items.push(item);
+            }
+        }
+        if items.len() == before {
+            break;
+        }
+        page += 1;
     }
-    pub async fn fetch_card_detail(
-        &self,
-        detail: TcgCollectorCardDetail,
-    ) -> Result<PtcgJpCard, Error> {
-        let document = scraper::Html::parse_document(&detail.html);
-
-        let desc_sel = &Selector::parse("#card-description").unwrap();
-        let desc = document.select(desc_sel).next().map(|d| d.inner_trim());
-
-        let skill1_name_en_sel = &Selector::parse(
-            "#card-info-body > div.card-attack > div > div.card-attack-header-text > div",
-        )
-        .unwrap();
-        let skill1_name_en = document
-            .select(skill1_name_en_sel)
+    info!("{}: {} items on {} pages", url, items.len(), page - 1);
+    Ok(items)
+}
+
+fn parse_exps(document: &Html) -> Vec<TcgCollectorExpansion> {
+    // example: Mar 22, 2024
+    let grid_items_sel = Selector::parse(".expansion-logo-grid-item").unwrap();
+    let grid_items = document.select(&grid_items_sel);
+    let mut exps = vec![];
+    for item in grid_items {
+        let sel = &Selector::parse(".expansion-logo-grid-item-release-date").unwrap();
+        let mut selected_release_date = item.select(sel);
+        let release_date = selected_release_date.next().unwrap().inner_trim();
+        let d = chrono::NaiveDate::parse_from_str(&release_date, "%b %d, %Y").unwrap();
+
+        let name_sel = &Selector::parse(".expansion-logo-grid-item-expansion-name").unwrap();
+        let name_el = item.select(name_sel).next().unwrap();
+        let name = name_el.inner_trim();
+
+        let code_sel = &Selector::parse(".expansion-logo-grid-item-expansion-code").unwrap();
+        let maybe_code = item
+            .select(code_sel)
             .next()
-            .map(|s| s.inner_trim());
-
-        let skill1_damage_sel = &Selector::parse(
-            "#card-info-body > div.card-attack > div > div.card-attack-header-text > span",
-        )
-        .unwrap();
-        let skill1_damage = document
-            .select(skill1_damage_sel)
+            .map(|e| e.inner_lowercase_trim());
+
+        let Some(code) = maybe_code else {
+            continue;
+        };
+        let link = name_el
+            .attr("href")
+            .map(|s| format!("https://www.tcgcollector.com{}", s));
+
+        let symbol_sel = &Selector::parse(".expansion-symbol").unwrap();
+        let symbol_src = item
+            .select(symbol_sel)
             .next()
-            .map(|s| s.inner_trim());
-
-        let card = PtcgJpCard {
-            name: detail.name,
-            number: detail.number,
-            exp_code: detail.exp_code,
-            rarity: detail.rarity,
-            desc,
-            skill1_name_en,
-            skill1_damage,
+            .map(|el| el.attr("src").unwrap().to_string());
+
+        let logo_sel = &Selector::parse(".expansion-logo-grid-item-expansion-logo").unwrap();
+        let logo_src = item
+            .select(logo_sel)
+            .next()
+            .map(|el| el.attr("src").unwrap().to_string());
+
+        let exp = TcgCollectorExpansion {
+            name,
+            code,
+            link,
+            symbol_src,
+            logo_src,
+            release_date: d,
         };
-        Ok(card)
+        exps.push(exp);
     }
+    exps
+}
+
+#[derive(Debug, Clone)]
+pub struct TcgCollectorCard {
+    pub name: String,
+    pub number: String,
+    pub exp_code: String,
+    pub url: String,
+    pub rarity: PtcgRarity,
+    pub image_src: Option<String>,
+}
+
+fn parse_card_list(document: &Html) -> Vec<TcgCollectorCard> {
+    let exp_code_sel = &Selector::parse("#card-search-result-title-expansion-code").unwrap();
+    let exp_code = document
+        .select(exp_code_sel)
+        .next()
+        .map(|e| e.inner_lowercase_trim())
+        .unwrap_or_default();
+
+    let card_items_sel = &Selector::parse(".card-list-item").unwrap();
+    let mut cards = vec![];
+    for item in document.select(card_items_sel) {
+        let name_sel = &Selector::parse(".card-list-item-entry-text").unwrap();
+        let name_el = item.select(name_sel).next().unwrap();
+        let url = format!(
+            "https://www.tcgcollector.com{}",
+            name_el.attr("href").unwrap()
+        );
+        let name = name_el.inner_trim();
+
+        let number_sel = &Selector::parse(".card-list-item-card-number > span").unwrap();
+        let number = item.select(number_sel).next().unwrap().inner_trim();
+
+        let rarity_sel = &Selector::parse(".card-rarity-symbol").unwrap();
+        let rarity = item
+            .select(rarity_sel)
+            .next()
+            .map(|s| s.attr("title").unwrap_or_default())
+            .unwrap_or_default();
+        let rarity = RaritySource::TcgCollector.parse_or_unknown(rarity);
+
+        let image_sel = &Selector::parse(".card-list-item-image img").unwrap();
+        let image_src = item
+            .select(image_sel)
+            .next()
+            .and_then(|el| el.attr("data-src").or_else(|| el.attr("src")))
+            .map(|src| src.to_string());
+        cards.push(TcgCollectorCard {
+            name,
+            number,
+            exp_code: exp_code.clone(),
+            url,
+            rarity,
+            image_src,
+        });
+    }
+    cards
 }
 
 #[derive(Debug)]
-pub struct PtcgJpExpansion {
+pub struct TcgCollectorExpansion {
     pub name: String,
     pub code: String,
     pub link: Option<String>,
@@ -202,53 +266,152 @@ pub struct TcgCollectorCardRarity {
     pub url: String,
 }
 
-#[derive(Debug, Clone)]
-pub struct TcgCollectorCardDetail {
-    pub name: String,
-    pub number: String,
-    pub exp_code: String,
-    pub html: String,
-    pub url: String,
-    pub rarity: Option<PtcgRarity>,
-}
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct PtcgJpCard {
     pub name: String,
     pub number: String,
     pub exp_code: String,
     pub desc: Option<String>,
+    pub hp: Option<String>,
     pub skill1_name_en: Option<String>,
     pub skill1_damage: Option<String>,
+    pub attacks: Vec<PtcgAttack>,
+    pub url: String,
     pub rarity: Option<PtcgRarity>,
 }
 
-struct TcgCollectorRarity<'a>(&'a str);
-
-impl<'a> From<TcgCollectorRarity<'a>> for PtcgRarity {
-    fn from(value: TcgCollectorRarity<'a>) -> Self {
-        match value.0 {
-            "Ultra Rare (UR)" => PtcgRarity::UR,
-            "Shiny Super Rare (SSR)" => PtcgRarity::SSR,
-            "ACE SPEC Rare (ACE)" => PtcgRarity::ACE,
-            "Hyper Rare (HR)" => PtcgRarity::HR,
-            "Super Rare (SR)" => PtcgRarity::SR,
-            "Special Art Rare (SAR)" => PtcgRarity::SAR,
-            "Character Super Rare (CSR)" => PtcgRarity::CSR,
-            "Art Rare (AR)" => PtcgRarity::AR,
-            "Character Rare (CHR)" => PtcgRarity::CHR,
-            "Shiny (S)" => PtcgRarity::S,
-            "Amazing Rare" => PtcgRarity::A,
-            "Rare Holo" => PtcgRarity::H,
-            "Radiant Rare (K)" => PtcgRarity::K,
-            "Promo" => PtcgRarity::PR,
-            "Triple Rare (RRR)" => PtcgRarity::RRR,
-            "Double Rare (RR)" => PtcgRarity::RR,
-            "Rare (R)" => PtcgRarity::R,
-            "Uncommon (U)" => PtcgRarity::U,
-            "Common (C)" => PtcgRarity::C,
-            "Trainer Rare (TR)" => PtcgRarity::TR,
-            _ => PtcgRarity::Unknown,
-        }
+/// Everything but the fields only the card list shows.
+pub fn parse_card_detail(html: &str) -> PtcgJpCard {
+    let document = scraper::Html::parse_document(html);
+
+    let desc_sel = &Selector::parse("#card-description").unwrap();
+    let desc = document.select(desc_sel).next().map(|d| d.inner_trim());
+
+    let hp_sel = &Selector::parse("#card-hit-points").unwrap();
+    let hp = document.select(hp_sel).next().map(|d| d.inner_trim());
+
+    let attacks = parse_attacks(document.root_element());
+    PtcgJpCard {
+        desc,
+        hp,
+        skill1_name_en: attacks.first().map(|a| a.name.clone()),
+        skill1_damage: attacks.first().and_then(|a| a.damage.clone()),
+        attacks,
+        ..Default::default()
+    }
+}
+
+fn parse_attacks(root: ElementRef) -> Vec<PtcgAttack> {
+    let attack_sel = Selector::parse("#card-info-body > div.card-attack").unwrap();
+    let name_sel = Selector::parse(".card-attack-header-text > div").unwrap();
+    let damage_sel = Selector::parse(".card-attack-header-text > span").unwrap();
+    let cost_sel = Selector::parse(".card-attack-header .energy-type-symbol").unwrap();
+    let effect_sel = Selector::parse(".card-attack-description").unwrap();
+    root.select(&attack_sel)
+        .filter_map(|attack| {
+            let name = attack.select(&name_sel).next()?.inner_trim();
+            let cost = attack
+                .select(&cost_sel)
+                .filter_map(|e| e.attr("title"))
+                .map(|t| t.trim_end_matches(" Energy").to_string())
+                .collect();
+            let damage = attack
+                .select(&damage_sel)
+                .next()
+                .map(|s| s.inner_trim())
+                .filter(|d| !d.is_empty());
+            let effect = attack
+                .select(&effect_sel)
+                .next()
+                .map(|s| s.text().collect::<String>().trim().to_string())
+                .filter(|e| !e.is_empty());
+            Some(PtcgAttack {
+                name,
+                cost,
+                damage,
+                effect,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse_every_attack() {
+        let source = r#"
+<div id="card-info-body">
+  <div class="card-attack">
+    <div class="card-attack-header">
+      <img class="energy-type-symbol" title="Fire Energy">
+      <div class="card-attack-header-text"><div>Ember</div><span>30</span></div>
+    </div>
+    <p class="card-attack-description">Discard an <b>Energy</b> from this Pokémon.</p>
+  </div>
+  <div class="card-attack">
+    <div class="card-attack-header">
+      <img class="energy-type-symbol" title="Fire Energy">
+      <img class="energy-type-symbol" title="Colorless Energy">
+      <div class="card-attack-header-text"><div>Flamethrower</div><span>90</span></div>
+    </div>
+  </div>
+</div>"#;
+        let document = scraper::Html::parse_document(source);
+        let attacks = parse_attacks(document.root_element());
+        assert_eq!(
+            attacks,
+            vec![
+                PtcgAttack {
+                    name: "Ember".to_string(),
+                    cost: vec!["Fire".to_string()],
+                    damage: Some("30".to_string()),
+                    effect: Some("Discard an Energy from this Pokémon.".to_string()),
+                },
+                PtcgAttack {
+                    name: "Flamethrower".to_string(),
+                    cost: vec!["Fire".to_string(), "Colorless".to_string()],
+                    damage: Some("90".to_string()),
+                    effect: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn release_window_bounds_are_inclusive() {
+        let date = |d| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+        let window = ReleaseWindow {
+            since: Some(date("2024-01-01")),
+            until: Some(date("2024-03-31")),
+        };
+        assert!(window.contains(date("2024-01-01")));
+        assert!(window.contains(date("2024-03-31")));
+        assert!(!window.contains(date("2023-12-31")));
+        assert!(ReleaseWindow::default().contains(date("1996-10-20")));
+    }
+
+    #[test]
+    fn parse_detail_fields() {
+        let source = r#"
+<div id="card-hit-points">60</div>
+<div id="card-description">It stores electricity in its cheeks.</div>
+<div id="card-info-body">
+  <div class="card-attack">
+    <div class="card-attack-header">
+      <img class="energy-type-symbol" title="Lightning Energy">
+      <div class="card-attack-header-text"><div>Thunder Jolt</div><span>30</span></div>
+    </div>
+  </div>
+</div>"#;
+        let card = parse_card_detail(source);
+        assert_eq!(card.hp.as_deref(), Some("60"));
+        assert_eq!(
+            card.desc.as_deref(),
+            Some("It stores electricity in its cheeks.")
+        );
+        assert_eq!(card.skill1_name_en.as_deref(), Some("Thunder Jolt"));
+        assert_eq!(card.skill1_damage.as_deref(), Some("30"));
+        assert_eq!(card.attacks.len(), 1);
     }
 }
@@ -1,89 +1,359 @@
-use scraper::Selector;
+use lazy_static::lazy_static;
+use regex::Regex;
+use scraper::{ElementRef, Selector};
+use strum::EnumString;
 
 use crate::{
-    domain::LastFetchedAt,
-    error::{Error, ErrorCode},
+    domain::{Language, LastFetchedAt},
+    error::Error,
 };
 
-pub(crate) struct WsScraper {}
+#[derive(Debug, Clone, Copy, PartialEq, EnumString, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum WsRegion {
+    Jp,
+    En,
+}
+
+impl WsRegion {
+    pub fn base_url(&self) -> &'static str {
+        match self {
+            WsRegion::Jp => "https://ws-tcg.com",
+            WsRegion::En => "https://en.ws-tcg.com",
+        }
+    }
+    pub fn language(&self) -> Language {
+        match self {
+            WsRegion::Jp => Language::Ja,
+            WsRegion::En => Language::En,
+        }
+    }
+    fn search_url(&self) -> String {
+        match self {
+            WsRegion::Jp => format!("{}/cardlist/search", self.base_url()),
+            WsRegion::En => format!("{}/cardlist/searchresults/", self.base_url()),
+        }
+    }
+    fn detail_url(&self, code: &str) -> String {
+        match self {
+            WsRegion::Jp => format!("{}/cardlist/?cardno={}", self.base_url(), code),
+            WsRegion::En => format!("{}/cardlist/list/?cardno={}", self.base_url(), code),
+        }
+    }
+}
+
+/// Strips the `E` the English site inserts after the dash of a card number,
+/// e.g. `BD/W54-E001` -> `BD/W54-001` and `BD/W54-TE01` -> `BD/W54-T01`, so
+/// English printings can be linked to their Japanese counterparts.
+pub fn base_code(code: &str) -> String {
+    lazy_static! {
+        static ref RE: Regex = Regex::new("-([A-Z]*?)E([0-9])").unwrap();
+    }
+    RE.replace(code, "-${1}${2}").to_string()
+}
+
+pub(crate) struct WsScraper {
+    pub region: WsRegion,
+}
 impl WsScraper {
     pub async fn get_source(url: &str) -> Result<String, Error> {
         Ok(reqwest::Client::new().get(url).send().await?.text().await?)
     }
 
-    pub async fn get_total_pages(&self) -> Result<i32, Error> {
-        let url = "https://ws-tcg.com/cardlist/search";
-        let source = Self::get_source(url).await?;
-        let document = scraper::Html::parse_document(&source);
-        let selector =
-            Selector::parse("#searchResults > p:nth-child(4) > span:nth-child(12) > a").unwrap();
-        let mut total_page_selection = document.select(&selector);
-        let total_pages: i32 = total_page_selection
-            .next()
-            .ok_or(Error::FieldMissing("total page not found".to_string()))?
-            .inner_html()
-            .replace(',', "")
-            .parse()?;
-        Ok(total_pages)
+    /// Page count of the JP card search; the EN search does not show one.
+    pub async fn get_total_pages(&self) -> Result<Option<i32>, Error> {
+        if self.region == WsRegion::En {
+            return Ok(None);
+        }
+        let source = Self::get_source(&self.region.search_url()).await?;
+        let total_pages = parse_total_pages(&source)
+            .ok_or(Error::FieldMissing("total page not found".to_string()))??;
+        Ok(Some(total_pages))
     }
 
-    pub(crate) async fn scrape_by_page(
+    /// Page count of one expansion's JP search results. `None` on the EN site
+    /// and when the results show no pager.
+    pub async fn get_expansion_total_pages(
         &self,
-        page_no: i32,
-    ) -> Result<Vec<Result<WsCard, ErrorCode>>, Error> {
-        let url = format!("https://ws-tcg.com/cardlist/search?page={}", page_no);
+        expansion_id: &str,
+    ) -> Result<Option<i32>, Error> {
+        if self.region == WsRegion::En {
+            return Ok(None);
+        }
+        let url = format!("{}?expansion={}", self.region.search_url(), expansion_id);
         let source = Self::get_source(&url).await?;
+        parse_total_pages(&source).transpose()
+    }
+
+    /// Expansions listed on the search page, newest first.
+    pub(crate) async fn fetch_expansions(&self) -> Result<Vec<WsExpansion>, Error> {
+        let source = Self::get_source(&self.region.search_url()).await?;
         let document = scraper::Html::parse_document(&source);
-        let selector = Selector::parse("table.search-result-table tbody tr").unwrap();
-        let trs = document.select(&selector);
-        let mut results = vec![];
-        for tr in trs {
-            let selector = &Selector::parse("h4").unwrap();
-            let set_name = tr
-                .select(selector)
-                .next()
-                .ok_or(Error::FieldMissing("set_name not found".to_string()))?
-                .text()
-                .last()
-                .ok_or(Error::FieldMissing("set_name not found".to_string()))?;
-            let selector = &Selector::parse("a span").unwrap();
-            let mut spans = tr.select(selector);
-            let card_name = spans
-                .next()
-                .ok_or(Error::FieldMissing("card_name not found".to_string()))?;
-            let card_no = spans.next().unwrap().inner_html();
-            let a = card_no.clone();
-            let (set_code, _) = a.split_once('/').unwrap();
-            let selector = &Selector::parse("img").unwrap();
-            let img_src = tr
-                .select(selector)
-                .next()
-                .ok_or(Error::FieldMissing("img_src not found".to_string()))?
-                .value()
-                .attr("src")
-                .ok_or(Error::FieldMissing("src not found".to_string()))?;
-            let selector = &Selector::parse("td span").unwrap();
-            let mut rarity: Option<String> = None;
-            for span in tr.select(selector) {
-                if span.inner_html().contains("レアリティ") {
-                    rarity = Some(span.inner_html().replace("レアリティ：", ""));
-                    break;
+        let selector = Selector::parse("select[name=\"expansion\"] option").unwrap();
+        let expansions = document
+            .select(&selector)
+            .filter_map(|option| {
+                let id = option.value().attr("value")?.trim();
+                if id.is_empty() {
+                    return None;
+                }
+                Some(WsExpansion {
+                    id: id.to_string(),
+                    name: option.text().collect::<String>().trim().to_string(),
+                })
+            })
+            .collect();
+        Ok(expansions)
+    }
+
+    pub(crate) async fn scrape_by_page(&self, page_no: i32) -> Result<Vec<WsCard>, Error> {
+        let url = format!("{}?page={}", self.region.search_url(), page_no);
+        self.scrape_search_result(&url).await
+    }
+
+    pub(crate) async fn scrape_expansion_page(
+        &self,
+        expansion_id: &str,
+        page_no: i32,
+    ) -> Result<Vec<WsCard>, Error> {
+        let url = format!(
+            "{}?expansion={}&page={}",
+            self.region.search_url(),
+            expansion_id,
+            page_no
+        );
+        self.scrape_search_result(&url).await
+    }
+
+    async fn scrape_search_result(&self, url: &str) -> Result<Vec<WsCard>, Error> {
+        let source = Self::get_source(url).await?;
+        match self.region {
+            WsRegion::Jp => parse_search_result(&source),
+            WsRegion::En => parse_en_search_result(&source),
+        }
+    }
+
+    pub(crate) async fn scrape_card_detail(&self, code: &str) -> Result<WsCardDetail, Error> {
+        let source = Self::get_source(&self.region.detail_url(code)).await?;
+        parse_card_detail(&source, code)
+    }
+}
+
+/// The last page link of the JP search pager, if there is one.
+fn parse_total_pages(source: &str) -> Option<Result<i32, Error>> {
+    let document = scraper::Html::parse_document(source);
+    let selector =
+        Selector::parse("#searchResults > p:nth-child(4) > span:nth-child(12) > a").unwrap();
+    let last_page = document.select(&selector).next()?;
+    Some(
+        last_page
+            .inner_html()
+            .replace(',', "")
+            .parse()
+            .map_err(Error::from),
+    )
+}
+
+fn parse_search_result(source: &str) -> Result<Vec<WsCard>, Error> {
+    let document = scraper::Html::parse_document(source);
+    let selector = Selector::parse("table.search-result-table tbody tr").unwrap();
+    let trs = document.select(&selector);
+    let mut results = vec![];
+    for tr in trs {
+        let selector = &Selector::parse("h4").unwrap();
+        let set_name = tr
+            .select(selector)
+            .next()
+            .ok_or(Error::FieldMissing("set_name not found".to_string()))?
+            .text()
+            .last()
+            .ok_or(Error::FieldMissing("set_name not found".to_string()))?;
+        let selector = &Selector::parse("a span").unwrap();
+        let mut spans = tr.select(selector);
+        let card_name = spans
+            .next()
+            .ok_or(Error::FieldMissing("card_name not found".to_string()))?;
+        let card_no = spans.next().unwrap().inner_html();
+        let a = card_no.clone();
+        let (set_code, _) = a.split_once('/').unwrap();
+        let selector = &Selector::parse("img").unwrap();
+        let img_src = tr
+            .select(selector)
+            .next()
+            .ok_or(Error::FieldMissing("img_src not found".to_string()))?
+            .value()
+            .attr("src")
+            .ok_or(Error::FieldMissing("src not found".to_string()))?;
+        let selector = &Selector::parse("td span").unwrap();
+        let mut rarity: Option<String> = None;
+        for span in tr.select(selector) {
+            if span.inner_html().contains("レアリティ") {
+                rarity = Some(span.inner_html().replace("レアリティ：", ""));
+                break;
+            }
+        }
+        let last_fetched_at = LastFetchedAt::default();
+        let card = WsCard {
+            name: card_name.inner_html().trim().to_string(),
+            base_code: card_no.clone(),
+            code: card_no,
+            set_code: set_code.to_string(),
+            img_src: img_src.to_owned(),
+            rarity,
+            set_name: set_name.to_string().replacen('-', "", 1),
+            language: Language::Ja,
+            counterpart_name: None,
+            last_fetched_at,
+        };
+        results.push(card)
+    }
+    Ok(results)
+}
+
+// The English list is a grid of cards rather than a table; the expansion and
+// rarity sit in labelled `span.unit` blocks below the name.
+fn parse_en_search_result(source: &str) -> Result<Vec<WsCard>, Error> {
+    let document = scraper::Html::parse_document(source);
+    let selector = Selector::parse(".search-result-list li").unwrap();
+    let mut results = vec![];
+    for li in document.select(&selector) {
+        let selector = &Selector::parse(".card-name").unwrap();
+        let name = li
+            .select(selector)
+            .next()
+            .ok_or(Error::FieldMissing("card_name not found".to_string()))?
+            .text()
+            .collect::<String>();
+        let selector = &Selector::parse(".card-number").unwrap();
+        let code = li
+            .select(selector)
+            .next()
+            .ok_or(Error::FieldMissing("card_no not found".to_string()))?
+            .text()
+            .collect::<String>()
+            .trim()
+            .to_string();
+        let (set_code, _) = code.split_once('/').ok_or(Error::FieldMissing(format!(
+            "set_code not found in {}",
+            code
+        )))?;
+        let selector = &Selector::parse("img").unwrap();
+        let img_src = li
+            .select(selector)
+            .next()
+            .and_then(|img| img.value().attr("src"))
+            .ok_or(Error::FieldMissing("img_src not found".to_string()))?;
+        let selector = &Selector::parse("span.unit").unwrap();
+        let mut set_name = None;
+        let mut rarity = None;
+        for span in li.select(selector) {
+            let text = span.text().collect::<String>();
+            if let Some((label, value)) = text.split_once(['：', ':']) {
+                match label.trim() {
+                    "Expansion" => set_name = Some(value.trim().to_string()),
+                    "Rarity" => rarity = Some(value.trim().to_string()),
+                    _ => {}
                 }
             }
-            let last_fetched_at = LastFetchedAt::default();
-            let card = WsCard {
-                name: card_name.inner_html().trim().to_string(),
-                code: card_no,
-                set_code: set_code.to_string(),
-                img_src: img_src.to_owned(),
-                rarity,
-                set_name: set_name.to_string().replacen('-', "", 1),
-                last_fetched_at,
-            };
-            results.push(Ok(card))
         }
-        Ok(results)
+        let card = WsCard {
+            name: name.trim().to_string(),
+            base_code: base_code(&code),
+            set_code: set_code.to_string(),
+            img_src: img_src.to_owned(),
+            rarity,
+            set_name: set_name.ok_or(Error::FieldMissing("set_name not found".to_string()))?,
+            language: Language::En,
+            counterpart_name: None,
+            last_fetched_at: LastFetchedAt::default(),
+            code,
+        };
+        results.push(card)
     }
+    Ok(results)
+}
+
+fn parse_card_detail(source: &str, code: &str) -> Result<WsCardDetail, Error> {
+    let document = scraper::Html::parse_document(source);
+    let selector = Selector::parse("table.card-detail-table th").unwrap();
+    let mut detail = WsCardDetail {
+        code: code.to_string(),
+        ..Default::default()
+    };
+    let mut found = false;
+    for th in document.select(&selector) {
+        let Some(td) = th.next_siblings().find_map(ElementRef::wrap) else {
+            continue;
+        };
+        found = true;
+        let text = td.text().collect::<String>().trim().to_string();
+        match th.inner_html().trim() {
+            "サイド" | "Side" => detail.side = image_names(td).first().map(|s| side_name(s)),
+            "色" | "Color" => detail.color = image_names(td).into_iter().next(),
+            "種類" | "Card Type" => detail.card_type = Some(text).filter(|t| !t.is_empty()),
+            "レベル" | "Level" => detail.level = text.parse().ok(),
+            "コスト" | "Cost" => detail.cost = text.parse().ok(),
+            "パワー" | "Power" => detail.power = text.parse().ok(),
+            "ソウル" | "Soul" => detail.soul = Some(image_names(td).len() as i32),
+            "トリガー" | "Trigger" => detail.triggers = image_names(td),
+            "特徴" | "Traits" => {
+                detail.traits = text
+                    .split(['・', '/'])
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty() && t != "-")
+                    .collect()
+            }
+            "テキスト" | "Text" => detail.text = Some(text).filter(|t| !t.is_empty()),
+            _ => {}
+        }
+    }
+    if !found {
+        return Err(Error::FieldMissing(format!(
+            "card detail of {} not found",
+            code
+        )));
+    }
+    Ok(detail)
+}
+
+// example: /wordpress/wp-content/images/cardlist/_partimages/yellow.gif -> yellow
+fn image_names(td: ElementRef) -> Vec<String> {
+    let selector = Selector::parse("img").unwrap();
+    td.select(&selector)
+        .filter_map(|img| img.value().attr("src"))
+        .filter_map(|src| src.rsplit('/').next())
+        .filter_map(|file| file.split('.').next())
+        .map(|name| name.to_string())
+        .collect()
+}
+
+fn side_name(image_name: &str) -> String {
+    match image_name {
+        "w" => String::from("Weiß"),
+        "s" => String::from("Schwarz"),
+        other => other.to_string(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WsExpansion {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WsCardDetail {
+    pub code: String,
+    pub side: Option<String>,
+    pub color: Option<String>,
+    pub card_type: Option<String>,
+    pub level: Option<i32>,
+    pub cost: Option<i32>,
+    pub power: Option<i32>,
+    pub soul: Option<i32>,
+    pub triggers: Vec<String>,
+    pub traits: Vec<String>,
+    pub text: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -94,5 +364,70 @@ pub struct WsCard {
     pub img_src: String,
     pub rarity: Option<String>,
     pub set_name: String,
+    pub language: Language,
+    /// Card number with the English `E` marker removed, shared by both printings.
+    pub base_code: String,
+    /// Name of the printing with the same base code in the other language.
+    pub counterpart_name: Option<String>,
     pub last_fetched_at: LastFetchedAt,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse_detail_table() {
+        let source = r#"
+<table class="card-detail-table">
+  <tr><th>カード番号</th><td>BD/W63-036</td><th>レアリティ</th><td>RR</td></tr>
+  <tr><th>サイド</th><td><img src="/wordpress/wp-content/images/cardlist/_partimages/w.gif"></td>
+      <th>種類</th><td>キャラ</td></tr>
+  <tr><th>色</th><td><img src="/wordpress/wp-content/images/cardlist/_partimages/yellow.gif"></td></tr>
+  <tr><th>レベル</th><td>3</td><th>コスト</th><td>2</td></tr>
+  <tr><th>パワー</th><td>10000</td>
+      <th>ソウル</th><td><img src="/_partimages/soul.gif"><img src="/_partimages/soul.gif"></td></tr>
+  <tr><th>トリガー</th><td><img src="/_partimages/soul.gif"></td>
+      <th>特徴</th><td>音楽・Poppin'Party</td></tr>
+  <tr><th>テキスト</th><td>【自】 このカードが手札から舞台に置かれた時</td></tr>
+</table>"#;
+        let detail = parse_card_detail(source, "BD/W63-036").unwrap();
+        assert_eq!(
+            detail,
+            WsCardDetail {
+                code: "BD/W63-036".to_string(),
+                side: Some("Weiß".to_string()),
+                color: Some("yellow".to_string()),
+                card_type: Some("キャラ".to_string()),
+                level: Some(3),
+                cost: Some(2),
+                power: Some(10000),
+                soul: Some(2),
+                triggers: vec!["soul".to_string()],
+                traits: vec!["音楽".to_string(), "Poppin'Party".to_string()],
+                text: Some("【自】 このカードが手札から舞台に置かれた時".to_string()),
+            }
+        );
+    }
+    #[test]
+    fn base_code_strips_english_marker() {
+        assert_eq!(base_code("BD/W54-E001"), "BD/W54-001");
+        assert_eq!(base_code("BD/W54-TE01"), "BD/W54-T01");
+        assert_eq!(base_code("BD/WE35-001"), "BD/WE35-001");
+        assert_eq!(base_code("BD/W54-001"), "BD/W54-001");
+    }
+    #[test]
+    fn total_pages_from_pager() {
+        let spans: String = (1..=11).map(|n| format!("<span>{n}</span>")).collect();
+        let source = format!(
+            r#"<div id="searchResults"><p></p><p></p><p></p>
+<p>{spans}<span><a href="?page=1234">1,234</a></span></p></div>"#
+        );
+        assert_eq!(parse_total_pages(&source).unwrap().unwrap(), 1234);
+        let single_page = r#"<div id="searchResults"><p></p><p></p><p></p><p></p></div>"#;
+        assert!(parse_total_pages(single_page).is_none());
+    }
+    #[test]
+    fn parse_detail_missing_table() {
+        assert!(parse_card_detail("<html></html>", "BD/W63-036").is_err());
+    }
+}
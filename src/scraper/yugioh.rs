@@ -2,6 +2,8 @@ use derive_builder::Builder;
 use fantoccini::{wd::Capabilities, ClientBuilder, Locator};
 use scraper::Selector;
 
+use crate::domain::LastFetchedAt;
+
 pub(crate) struct YugiohScraper {
     cap: Capabilities,
     url: String,
@@ -170,4 +172,6 @@ pub struct YugiohPrinting {
     pub remark: String,
     pub expansion_name: String,
     pub r#ref: String,
+    #[builder(default)]
+    pub last_fetched_at: LastFetchedAt,
 }
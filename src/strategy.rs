@@ -1,8 +1,8 @@
 use std::{collections::HashMap, ops::Range};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(tag = "source", rename_all = "snake_case")]
 pub enum Source {
     Manual(ManualStrategy),
@@ -11,39 +11,39 @@ pub enum Source {
     TcgCollector(TcgCollectorStrategy),
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ManualStrategy {
     Data(Data),
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct Data {
     card_data: Vec<CardData>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct CardData {
     number: String,
     name: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum PtcgStrategy {
     All,
     Pic,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum WikiStrategy {
     Data(WikiData),
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum TcgCollectorStrategy {
     Pic(TcgCollectorPic),
@@ -51,31 +51,34 @@ pub enum TcgCollectorStrategy {
     PicMappings(PicMappings),
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub struct PicByName {
     exps: Vec<String>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub struct TcgCollectorPic {
     range: Range<i32>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub struct PicMappings {
     mappings: HashMap<String, String>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct WikiData {
     url: url::Url,
     range: Option<Range<i32>>,
 }
 
 impl WikiData {
+    pub fn new(url: url::Url) -> Self {
+        Self { url, range: None }
+    }
     pub fn url(&self) -> url::Url {
         self.url.clone()
     }
@@ -120,6 +123,20 @@ mod tests {
         let _source: Source = serde_json::from_str(json).unwrap();
     }
     #[test]
+    fn test_wiki_data_round_trips() {
+        let url = "https://wiki.52poke.com/wiki/%E8%BF%9E%E5%87%BB%E5%A4%A7%E5%B8%88%EF%BC%88TCG%EF%BC%89";
+        let sources = vec![Source::Wiki(WikiStrategy::Data(WikiData::new(
+            url::Url::parse(url).unwrap(),
+        )))];
+        let json = serde_json::to_string(&sources).unwrap();
+        let sources: Vec<Source> = serde_json::from_str(&json).unwrap();
+        let Source::Wiki(WikiStrategy::Data(data)) = &sources[0] else {
+            panic!("{json}");
+        };
+        assert_eq!(data.url().as_str(), url);
+        assert_eq!(data.range(), None);
+    }
+    #[test]
     fn test_tcg_collector_by_name() {
         let json = r#"
   {
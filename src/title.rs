@@ -0,0 +1,156 @@
+//! Marketplace listing titles, e.g. `[【SV5K】ワイルドフォース]ピカチュウ【SAR】`,
+//! parsed with `grammar.pest`.
+
+use std::str::FromStr;
+
+use pest::{iterators::Pair, Parser};
+use pest_derive::Parser;
+
+#[derive(Parser)]
+#[grammar = "../grammar.pest"]
+struct TitleParser;
+
+#[derive(thiserror::Error, Debug)]
+#[error("invalid listing title {0}")]
+pub struct TitleError(#[from] Box<pest::error::Error<Rule>>);
+
+#[derive(Debug, PartialEq)]
+pub struct ListingTitle {
+    /// set code between the first 【】, e.g. `SV5K`
+    pub set_ref: String,
+    /// e.g. `B` for `【SM12a(B)】`
+    pub ref_desc: Option<String>,
+    pub set_name: Option<String>,
+    /// e.g. `プロモ` for a leading `【プロモ】`
+    pub promo: Option<String>,
+    pub card_name: String,
+    pub descriptors: Vec<TitleDescriptor>,
+}
+
+/// What follows the card name, telling printings of the same card apart.
+#[derive(Debug, PartialEq)]
+pub enum TitleDescriptor {
+    /// `【SAR】` or `[SAR]`, usually a rarity
+    Tag(String),
+    /// `(...)`
+    AlterArt(String),
+    /// `（...）`
+    TrainerName(String),
+    /// `(...）`, the mixed-width brackets some shops use for special arts
+    SpecialArt(String),
+}
+
+impl FromStr for ListingTitle {
+    type Err = TitleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let title = TitleParser::parse(Rule::TITLE, s.trim())
+            .map_err(Box::new)?
+            .next()
+            .expect("TITLE always yields one pair");
+        let mut listing = ListingTitle {
+            set_ref: String::new(),
+            ref_desc: None,
+            set_name: None,
+            promo: None,
+            card_name: String::new(),
+            descriptors: vec![],
+        };
+        for pair in title.into_inner() {
+            match pair.as_rule() {
+                Rule::REF => listing.set_ref = pair.as_str().trim().to_string(),
+                Rule::REF_DESC => listing.ref_desc = Some(inner_text(&pair)),
+                Rule::SET_NAME => {
+                    listing.set_name =
+                        Some(pair.as_str().trim().to_string()).filter(|name| !name.is_empty())
+                }
+                Rule::PROMO => listing.promo = Some(inner_text(&pair)),
+                Rule::CARD_NAME => listing.card_name = pair.as_str().trim().to_string(),
+                Rule::DESC => listing.descriptors.push(descriptor(pair)),
+                _ => {}
+            }
+        }
+        Ok(listing)
+    }
+}
+
+fn descriptor(desc: Pair<Rule>) -> TitleDescriptor {
+    let inner = desc.clone().into_inner().next();
+    match inner.as_ref().map(|p| p.as_rule()) {
+        Some(Rule::ALTER_ART) => TitleDescriptor::AlterArt(inner_text(&desc)),
+        Some(Rule::TRAINER_NAME) => TitleDescriptor::TrainerName(inner_text(&desc)),
+        Some(Rule::SPECIAL_ART) => TitleDescriptor::SpecialArt(inner_text(&desc)),
+        _ => TitleDescriptor::Tag(inner_text(&desc)),
+    }
+}
+
+/// The pair's text without its surrounding brackets.
+fn inner_text(pair: &Pair<Rule>) -> String {
+    let s = pair.as_str().trim();
+    let mut chars = s.chars();
+    chars.next();
+    chars.next_back();
+    chars.as_str().trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_set_and_card_name() {
+        let title: ListingTitle = "[【SV5K】ワイルドフォース]ピカチュウ".parse().unwrap();
+        assert_eq!(
+            title,
+            ListingTitle {
+                set_ref: "SV5K".to_string(),
+                ref_desc: None,
+                set_name: Some("ワイルドフォース".to_string()),
+                promo: None,
+                card_name: "ピカチュウ".to_string(),
+                descriptors: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn parse_promo_and_descriptors() {
+        let title: ListingTitle =
+            "[【SM-P】]【プロモ】ピカチュウ【PR】(マスターボールミラー)（ナンジャモ）"
+                .parse()
+                .unwrap();
+        assert_eq!(title.set_ref, "SM-P");
+        assert_eq!(title.set_name, None);
+        assert_eq!(title.promo.as_deref(), Some("プロモ"));
+        assert_eq!(title.card_name, "ピカチュウ");
+        assert_eq!(
+            title.descriptors,
+            vec![
+                TitleDescriptor::Tag("PR".to_string()),
+                TitleDescriptor::AlterArt("マスターボールミラー".to_string()),
+                TitleDescriptor::TrainerName("ナンジャモ".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_ref_desc_and_special_art() {
+        let title: ListingTitle = "[【SM12a(B)】タッグオールスターズ]リーリエ[SR](SA）"
+            .parse()
+            .unwrap();
+        assert_eq!(title.set_ref, "SM12a");
+        assert_eq!(title.ref_desc.as_deref(), Some("B"));
+        assert_eq!(
+            title.descriptors,
+            vec![
+                TitleDescriptor::Tag("SR".to_string()),
+                TitleDescriptor::SpecialArt("SA".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_titles_without_set_ref() {
+        assert!("ピカチュウ【SAR】".parse::<ListingTitle>().is_err());
+    }
+}